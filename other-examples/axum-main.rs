@@ -1,6 +1,11 @@
+// To run it copy this file over src/main.rs and `cargo run`. The theme comes from this crate's
+// src/theme.rs, which brings plotters along, the server needs:
+// cargo add axum tower-http --features tower-http/fs
 use axum::{
     Form, Router,
-    response::Html,
+    extract::Query,
+    http::header,
+    response::{Html, IntoResponse},
     routing::{get, post},
 };
 use reqwest;
@@ -8,11 +13,14 @@ use serde::Deserialize;
 use std::net::SocketAddr;
 use tower_http::services::ServeDir;
 
+use rust_syntax_revision::theme; //the palettes the charts are drawn with, see src/theme.rs
+
 #[tokio::main]
 async fn main() {
     let app = Router::new()
         .route("/validate", post(validate))
-        .fallback_service(ServeDir::new("static"));
+        .route("/theme.css", get(theme_css))
+        .fallback_service(ServeDir::new("other-examples/static")); //from the crate root
 
     let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
     println!("Listening on http://{}", addr);
@@ -32,3 +40,17 @@ async fn validate(Form(input): Form<UserInput>) -> Html<String> {
         Html(format!("<p>Hello, {}!</p>", input.name))
     }
 }
+
+#[derive(Deserialize)]
+struct ThemeQuery {
+    name: Option<String>,
+}
+
+// CSS variables for the page, mocha unless ?name= picks another theme
+async fn theme_css(Query(query): Query<ThemeQuery>) -> impl IntoResponse {
+    let theme = query
+        .name
+        .and_then(|name| theme::Theme::by_name(&name))
+        .unwrap_or_default();
+    ([(header::CONTENT_TYPE, "text/css")], theme.css_variables())
+}
@@ -0,0 +1,295 @@
+use plotters::coord::Shift;
+use plotters::prelude::*; //cargo add plotters -> for plotting
+use std::ops::Range;
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+use crate::theme::Theme;
+
+// how one axis of a chart gets its extent
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisOptions {
+    pub padding: f64,              // fraction of the data span added on both ends
+    pub log_scale: bool,           // plot log10 of the values, labels still show the real values
+    pub range: Option<Range<f64>>, // fixed range in data units, skips the data driven one
+}
+
+impl Default for AxisOptions {
+    fn default() -> Self {
+        AxisOptions {
+            padding: 0.05,
+            log_scale: false,
+            range: None,
+        }
+    }
+}
+
+// options for both axes of a 2D chart
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChartAxes {
+    pub x: AxisOptions,
+    pub y: AxisOptions,
+}
+
+// a resolved axis, range is in chart coordinates (log10 of the data for log axes)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    pub range: Range<f64>,
+    pub log_scale: bool,
+}
+
+impl Axis {
+    // Works out the axis extent from the data (or the override), NaN and infinite values are ignored.
+    pub fn fit<I>(values: I, options: &AxisOptions) -> Result<Self>
+    where
+        I: IntoIterator<Item = f64>,
+    {
+        let (min, max) = match &options.range {
+            Some(range) => {
+                if range.is_empty() {
+                    return Err(Error::invalid(format!(
+                        "axis range {}..{} is empty",
+                        range.start, range.end
+                    )));
+                }
+                (range.start, range.end)
+            }
+            None => values
+                .into_iter()
+                .filter(|v| v.is_finite())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+                    (lo.min(v), hi.max(v))
+                }),
+        };
+
+        if options.log_scale && min <= 0.0 {
+            return Err(Error::invalid(format!(
+                "log scale needs positive values, smallest value is {min}"
+            )));
+        }
+
+        let log_scale = options.log_scale;
+        let to_chart = |v: f64| if log_scale { v.log10() } else { v };
+
+        let range = if min > max {
+            0.0..1.0 //no finite data, just show something sensible
+        } else if options.range.is_some() {
+            to_chart(min)..to_chart(max) //user asked for exactly this
+        } else {
+            let (lo, hi) = (to_chart(min), to_chart(max));
+            let span = if hi > lo { hi - lo } else { lo.abs().max(1.0) }; //single value: pad around it
+            let pad = span * options.padding.max(0.0);
+            if hi > lo {
+                (lo - pad)..(hi + pad)
+            } else {
+                (lo - span / 2.0)..(hi + span / 2.0)
+            }
+        };
+
+        Ok(Axis { range, log_scale })
+    }
+
+    // data value -> chart coordinate
+    pub fn to_chart(&self, value: f64) -> f64 {
+        if self.log_scale { value.log10() } else { value }
+    }
+
+    // chart coordinate -> data value
+    pub fn to_data(&self, coord: f64) -> f64 {
+        if self.log_scale {
+            10f64.powf(coord)
+        } else {
+            coord
+        }
+    }
+
+    // tick label for a chart coordinate, shows the data value
+    pub fn label(&self, coord: f64) -> String {
+        let value = self.to_data(coord);
+        let magnitude = value.abs();
+        if magnitude < 1e-9 {
+            "0".to_string() //ticks at zero come out as tiny rounding errors
+        } else if !(1e-3..1e5).contains(&magnitude) {
+            format!("{value:.1e}")
+        } else {
+            let text = format!("{value:.3}");
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        }
+    }
+}
+
+// where a chart is drawn to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    Png, // raster file, the format actually follows the file extension (.png, .jpg, .bmp, ...)
+    Svg, // vector file
+    Rgb, // in-memory RGB buffer, nothing is written to disk
+}
+
+// output of a chart: path, size and format
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub path: PathBuf, // ignored by the Rgb backend
+    pub width: u32,
+    pub height: u32,
+    pub scale: f64, // DPI scaling, 2.0 doubles the pixel size along with fonts, markers and lines
+    pub backend: Backend,
+    pub theme: Theme, // colours, see theme.rs
+}
+
+impl RenderOptions {
+    // 800x600 PNG in the Mocha theme at the given path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        RenderOptions {
+            path: path.into(),
+            width: 800,
+            height: 600,
+            scale: 1.0,
+            backend: Backend::Png,
+            theme: Theme::MOCHA,
+        }
+    }
+
+    // a size in pixels at scale 1.0 -> the size to draw with
+    pub fn px(&self, size: u32) -> u32 {
+        (size as f64 * self.scale.max(0.0)).round().max(1.0) as u32
+    }
+
+    // the actual pixel size of the image
+    pub fn pixel_size(&self) -> (u32, u32) {
+        (self.px(self.width), self.px(self.height))
+    }
+}
+
+// what rendering produced
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rendered {
+    File(PathBuf),
+    Rgb {
+        width: u32,
+        height: u32,
+        pixels: Vec<u8>, // row major, 3 bytes per pixel
+    },
+}
+
+impl Rendered {
+    // where the chart went, as a progress message for the caller to print (the library prints nothing)
+    pub fn describe(&self, what: &str) -> String {
+        match self {
+            Rendered::File(path) => format!("Saved {what} to {}", path.display()),
+            Rendered::Rgb { width, height, .. } => format!("Rendered {what} to a {width}x{height} buffer"),
+        }
+    }
+}
+
+// Anything that can draw itself on a plotters drawing area, whatever the backend is.
+// render() below picks the backend so the figures don't have to care.
+pub trait Figure {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        options: &RenderOptions,
+    ) -> Result<()>
+    where
+        DB::ErrorType: 'static;
+}
+
+// draws a figure with the backend, size and path from the options
+pub fn render(figure: &impl Figure, options: &RenderOptions) -> Result<Rendered> {
+    let size = options.pixel_size();
+    match options.backend {
+        Backend::Png => {
+            let root = BitMapBackend::new(&options.path, size).into_drawing_area();
+            figure.draw(&root, options)?;
+            root.present()?;
+            Ok(Rendered::File(options.path.clone()))
+        }
+        Backend::Svg => {
+            let root = SVGBackend::new(&options.path, size).into_drawing_area();
+            figure.draw(&root, options)?;
+            root.present()?;
+            Ok(Rendered::File(options.path.clone()))
+        }
+        Backend::Rgb => {
+            let mut pixels = vec![0u8; size.0 as usize * size.1 as usize * 3];
+            {
+                // the backend borrows the buffer until it's dropped
+                let root = BitMapBackend::with_buffer(&mut pixels, size).into_drawing_area();
+                figure.draw(&root, options)?;
+                root.present()?;
+            }
+            Ok(Rendered::Rgb {
+                width: size.0,
+                height: size.1,
+                pixels,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(range: &Range<f64>, start: f64, end: f64) -> bool {
+        (range.start - start).abs() < 1e-12 && (range.end - end).abs() < 1e-12
+    }
+
+    #[test]
+    fn axis_from_the_data() {
+        // 5% of the span on both ends, NaN and infinity left out
+        let values = [-10.0, 5.0, f64::NAN, 10.0, f64::INFINITY];
+        let axis = Axis::fit(values, &AxisOptions::default()).unwrap();
+        assert!(close(&axis.range, -11.0, 11.0));
+
+        let one_value = Axis::fit([4.0, 4.0], &AxisOptions::default()).unwrap();
+        assert!(close(&one_value.range, 2.0, 6.0));
+        let nothing = Axis::fit([f64::NAN], &AxisOptions::default()).unwrap();
+        assert!(close(&nothing.range, 0.0, 1.0));
+
+        let fixed = AxisOptions {
+            range: Some(-1.0..3.0),
+            ..AxisOptions::default()
+        };
+        assert!(close(&Axis::fit([100.0], &fixed).unwrap().range, -1.0, 3.0));
+        let empty = AxisOptions {
+            range: Some(3.0..3.0),
+            ..AxisOptions::default()
+        };
+        assert_eq!(Axis::fit([1.0], &empty).unwrap_err().code(), "invalid_parameter");
+    }
+
+    #[test]
+    fn log_axis() {
+        let log = AxisOptions {
+            padding: 0.0,
+            log_scale: true,
+            range: None,
+        };
+        let axis = Axis::fit([1.0, 10.0, 1000.0], &log).unwrap();
+        assert!(close(&axis.range, 0.0, 3.0)); //in decades
+        assert_eq!(axis.to_chart(100.0), 2.0);
+        assert_eq!(axis.to_data(2.0), 100.0);
+        assert_eq!(axis.label(2.0), "100");
+
+        for values in [[0.0, 10.0], [-5.0, 10.0]] {
+            let error = Axis::fit(values, &log).unwrap_err();
+            assert_eq!(error.code(), "invalid_parameter");
+        }
+        let fixed = AxisOptions {
+            range: Some(0.0..10.0),
+            ..log
+        };
+        assert!(Axis::fit([1.0], &fixed).is_err()); //an override can't start at 0 either
+    }
+
+    #[test]
+    fn tick_labels() {
+        let axis = Axis::fit([0.0, 1.0], &AxisOptions::default()).unwrap();
+        assert_eq!(axis.label(1e-12), "0");
+        assert_eq!(axis.label(2.5), "2.5");
+        assert_eq!(axis.label(3.0), "3");
+        assert_eq!(axis.label(123_456.0), "1.2e5");
+        assert_eq!(axis.label(-0.0001), "-1.0e-4");
+    }
+}
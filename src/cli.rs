@@ -0,0 +1,525 @@
+use clap::{Parser, Subcommand, ValueEnum}; //cargo add clap --features derive -> command line arguments
+use serde_json::{Value, json}; //cargo add serde_json -> JSON output
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::chart::{self, Backend, RenderOptions};
+use crate::clustering::{Algorithm, ClusterCount, ClusterReport, Linkage};
+use crate::data::{self, LoadOptions, NullPolicy};
+use crate::error::{Error, Result};
+use crate::fitting::{self, FitPlotOptions, FitResult, Model};
+use crate::lessons::{self, Lesson};
+use crate::plotting::{self, ClusterPlotOptions, Projection};
+use crate::preprocessing::{Pipeline, Scaler};
+
+// Command line interface: `fit` and `cluster` run on a data file, `lessons` runs the walkthrough,
+// without a subcommand the binary runs every lesson.
+#[derive(Debug, Parser)]
+#[command(version, about = "Rust walkthrough with fitting and clustering on data files")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Fit a regression of one column on others and plot it
+    Fit {
+        #[command(flatten)]
+        input: Input,
+        /// Feature column, repeat for several features
+        #[arg(long, required = true)]
+        x: Vec<String>,
+        /// Target column
+        #[arg(long)]
+        y: String,
+        /// linear, poly<degree> (e.g. poly2), exp or log
+        #[arg(long, default_value = "linear", value_parser = parse_model)]
+        model: Model,
+        /// Confidence level of the shaded bands, between 0 and 1, 0 for none
+        #[arg(long, default_value = "0.95", value_parser = parse_level)]
+        bands: f64,
+        /// Also draw the residual diagnostics to this file
+        #[arg(long)]
+        residuals: Option<PathBuf>,
+        /// Chart file, .svg writes an SVG, anything else a PNG
+        #[arg(long, default_value = "fit.png")]
+        out: PathBuf,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Cluster rows by some columns and plot the clusters
+    Cluster {
+        #[command(flatten)]
+        input: Input,
+        /// Columns to cluster on, all f64 columns if left out
+        #[arg(long, value_delimiter = ',')]
+        columns: Vec<String>,
+        /// Number of clusters, or auto to pick it from 2..=8
+        #[arg(long, default_value = "auto", value_parser = parse_count)]
+        k: ClusterCount,
+        #[arg(long, value_enum, default_value_t = Method::Kmeans)]
+        algorithm: Method,
+        /// DBSCAN neighbourhood radius
+        #[arg(long, default_value_t = 0.5)]
+        eps: f64,
+        /// DBSCAN points needed to grow a cluster
+        #[arg(long, default_value_t = 3)]
+        min_points: usize,
+        /// Rescale the columns first
+        #[arg(long, value_enum, default_value_t = Scaling::None)]
+        scale: Scaling,
+        /// Plot the first two principal components instead of the first two columns
+        #[arg(long)]
+        pca: bool,
+        /// Rows with nulls: drop, mean, median or error
+        #[arg(long, value_enum, default_value_t = Nulls::Drop)]
+        nulls: Nulls,
+        /// Chart file, .svg writes an SVG, anything else a PNG
+        #[arg(long, default_value = "clusters.png")]
+        out: PathBuf,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// List or run the lessons of the walkthrough
+    Lessons {
+        #[command(subcommand)]
+        action: LessonAction,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LessonAction {
+    /// Show every lesson with a one line summary
+    List,
+    /// Run the named lessons in the given order
+    Run {
+        #[arg(required = true)]
+        names: Vec<String>,
+        /// Write the output to this file instead of the terminal
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Run every lesson in order
+    All {
+        /// Write the output to this file instead of the terminal
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+// the data file and how to read it, shared by the subcommands
+#[derive(Debug, clap::Args)]
+pub struct Input {
+    /// CSV, Parquet, JSON, NDJSON or IPC file
+    #[arg(long)]
+    input: PathBuf,
+    /// Force a column type, e.g. --dtype zip=str (str, f64, i64 or bool)
+    #[arg(long = "dtype", value_parser = parse_override)]
+    overrides: Vec<(String, polars::prelude::DataType)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Method {
+    Kmeans,
+    Gmm,
+    Agglomerative,
+    Dbscan,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Scaling {
+    None,
+    Standard,
+    Minmax,
+    Robust,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Nulls {
+    Drop,
+    Mean,
+    Median,
+    Error,
+}
+
+// runs a subcommand, results go to stdout as text or JSON, progress messages to stderr
+pub fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Fit {
+            input,
+            x,
+            y,
+            model,
+            bands,
+            residuals,
+            out,
+            format,
+        } => {
+            let df = input.load()?;
+            let features: Vec<&str> = x.iter().map(String::as_str).collect();
+            let options = FitPlotOptions {
+                model,
+                band_level: (bands > 0.0).then_some(bands),
+                render: render_options(&out),
+                residuals: residuals.as_deref().map(|path| RenderOptions {
+                    width: 1500,
+                    height: 500,
+                    ..render_options(path)
+                }),
+                ..FitPlotOptions::default()
+            };
+            let (result, rendered, residuals) =
+                fitting::fit_and_plot(&df, &features, &y, &options)?;
+            eprintln!("✅ {}", rendered.describe("fit plot"));
+            if let Some(rendered) = residuals {
+                eprintln!("✅ {}", rendered.describe("residual diagnostics"));
+            }
+
+            match format {
+                Format::Text => println!("{result}"),
+                Format::Json => println!("{:#}", fit_json(&result, &out)),
+            }
+        }
+        Command::Cluster {
+            input,
+            columns,
+            k,
+            algorithm,
+            eps,
+            min_points,
+            scale,
+            pca,
+            nulls,
+            out,
+            format,
+        } => {
+            let df = input.load()?;
+            let columns: Vec<String> = if columns.is_empty() {
+                df.get_columns()
+                    .iter()
+                    .filter(|c| *c.dtype() == polars::prelude::DataType::Float64)
+                    .map(|c| c.name().to_string())
+                    .collect()
+            } else {
+                columns
+            };
+            let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+
+            let algorithm = match algorithm {
+                Method::Kmeans => Algorithm::KMeans(k),
+                Method::Gmm => Algorithm::GaussianMixture(k),
+                Method::Agglomerative => Algorithm::Agglomerative(k, Linkage::Ward),
+                Method::Dbscan => Algorithm::Dbscan {
+                    tolerance: eps,
+                    min_points,
+                },
+            };
+            let scaler = match scale {
+                Scaling::None => None,
+                Scaling::Standard => Some(Scaler::Standard),
+                Scaling::Minmax => Some(Scaler::MinMax),
+                Scaling::Robust => Some(Scaler::Robust),
+            };
+            let options = ClusterPlotOptions {
+                algorithm,
+                nulls: match nulls {
+                    Nulls::Drop => NullPolicy::Drop,
+                    Nulls::Mean => NullPolicy::Mean,
+                    Nulls::Median => NullPolicy::Median,
+                    Nulls::Error => NullPolicy::Error,
+                },
+                projection: if pca {
+                    Projection::Pca
+                } else {
+                    Projection::Columns(0, 1)
+                },
+                preprocessing: scaler
+                    .map(|scaler| Pipeline::default().scale(scaler, &columns)),
+                render: render_options(&out),
+                ..ClusterPlotOptions::default()
+            };
+            let (report, rendered) = plotting::plot_dataframe(&df, &columns, &options)?;
+            report_progress(&report);
+            eprintln!("✅ {}", rendered.describe("clusters"));
+
+            match format {
+                Format::Text => println!("{report}"),
+                Format::Json => println!("{:#}", cluster_json(&report, &columns, &out)),
+            }
+        }
+        Command::Lessons { action } => match action {
+            LessonAction::List => {
+                for lesson in lessons::ALL {
+                    println!("{:<14} {}", lesson.name, lesson.summary);
+                }
+            }
+            LessonAction::Run { names, out } => {
+                // look every name up first, a typo shouldn't run half the list
+                let selected = names
+                    .iter()
+                    .map(|name| {
+                        lessons::find(name).ok_or_else(|| {
+                            Error::invalid(format!("no lesson called \"{name}\", see `lessons list`"))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                run_lessons(&selected, out.as_deref())?;
+            }
+            LessonAction::All { out } => {
+                let all: Vec<&Lesson> = lessons::ALL.iter().collect();
+                run_lessons(&all, out.as_deref())?;
+            }
+        },
+    }
+    Ok(())
+}
+
+// how the clustering got there, to stderr so stdout only holds the result
+fn report_progress(report: &ClusterReport) {
+    if report.dropped > 0 {
+        eprintln!("Dropped {} rows with null values", report.dropped);
+    }
+    if report.imputed > 0 {
+        eprintln!("Imputed {} null values", report.imputed);
+    }
+    let selection = &report.selection;
+    for score in &selection.scores {
+        eprintln!(
+            "k = {}: inertia = {:.3}, silhouette = {:.3}",
+            score.k, score.inertia, score.silhouette
+        );
+    }
+    if !selection.skipped.is_empty() {
+        eprintln!("Skipped k = {:?}, the algorithm failed on them", selection.skipped);
+    }
+    eprintln!("Chosen k = {} (elbow at k = {})", selection.k, selection.elbow);
+    if report.noise > 0 {
+        eprintln!("{} points marked as noise", report.noise);
+    }
+    if let Some(explained) = report.explained_variance {
+        eprintln!("PCA: the two components explain {:.1}% of the variance", 100.0 * explained);
+    }
+}
+
+// to the terminal as it goes, or captured lesson by lesson into a file
+fn run_lessons(selected: &[&Lesson], out: Option<&Path>) -> Result<()> {
+    match out {
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            for lesson in selected {
+                lesson.run(&mut stdout)?;
+            }
+        }
+        Some(path) => {
+            let mut file = std::fs::File::create(path)?;
+            for lesson in selected {
+                file.write_all(lesson.capture()?.as_bytes())?;
+                eprintln!("✅ {} written to {}", lesson.name, path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Input {
+    fn load(&self) -> Result<polars::prelude::DataFrame> {
+        let options = LoadOptions {
+            schema_overrides: self.overrides.clone(),
+            ..LoadOptions::default()
+        };
+        data::load_dataframe(&self.input, &options)
+    }
+}
+
+// PNG unless the file ends in .svg
+fn render_options(path: &Path) -> RenderOptions {
+    let svg = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+    RenderOptions {
+        backend: if svg { Backend::Svg } else { Backend::Png },
+        ..chart::RenderOptions::new(path)
+    }
+}
+
+fn fit_json(result: &FitResult, out: &Path) -> Value {
+    let coefficients: serde_json::Map<String, Value> = result
+        .terms
+        .iter()
+        .zip(&result.coefficients)
+        .map(|(term, coefficient)| (term.clone(), json!(coefficient)))
+        .collect();
+    json!({
+        "model": result.model.to_string(),
+        "features": result.features,
+        "target": result.target,
+        "intercept": result.intercept,
+        "coefficients": coefficients,
+        "r_squared": result.r_squared,
+        "adjusted_r_squared": result.adjusted_r_squared,
+        "rmse": result.rmse,
+        "aic": result.aic,
+        "bic": result.bic,
+        "n": result.y.len(),
+        "diagnostics": {
+            "durbin_watson": result.diagnostics.durbin_watson, //null for a perfect fit
+            "skewness": result.diagnostics.skewness,
+            "kurtosis": result.diagnostics.kurtosis,
+            "jarque_bera": result.diagnostics.jarque_bera,
+            "jarque_bera_p": result.diagnostics.jarque_bera_p,
+        },
+        "chart": out,
+    })
+}
+
+fn cluster_json(report: &ClusterReport, columns: &[&str], out: &Path) -> Value {
+    let centroids: Vec<Vec<f64>> = report.centroids.rows().into_iter().map(|row| row.to_vec()).collect();
+    json!({
+        "algorithm": report.algorithm.to_string(),
+        "columns": columns,
+        "k": report.selection.k,
+        "elbow": report.selection.elbow,
+        "sizes": report.sizes,
+        "centroids": centroids,
+        "noise": report.noise,
+        "dropped": report.dropped,
+        "imputed": report.imputed,
+        "explained_variance": report.explained_variance,
+        "inertia": report.inertia,
+        "silhouette": report.silhouette,
+        "davies_bouldin": report.davies_bouldin,
+        "calinski_harabasz": report.calinski_harabasz,
+        "labels": report.labels,
+        "chart": out,
+    })
+}
+
+// linear, poly<degree>, exp or log
+fn parse_model(text: &str) -> Result<Model, String> {
+    match text.to_lowercase().as_str() {
+        "linear" => Ok(Model::Linear),
+        "exp" | "exponential" => Ok(Model::Exponential),
+        "log" | "logarithmic" => Ok(Model::Logarithmic),
+        other => other
+            .strip_prefix("poly")
+            .and_then(|degree| degree.trim_start_matches(':').parse().ok())
+            .filter(|&degree| degree >= 1)
+            .map(Model::Polynomial)
+            .ok_or_else(|| format!("unknown model \"{text}\", use linear, poly<degree>, exp or log")),
+    }
+}
+
+// a band level strictly between 0 and 1, or 0 for no bands
+fn parse_level(text: &str) -> Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(level) if (0.0..1.0).contains(&level) => Ok(level),
+        _ => Err(format!("the band level must be between 0 and 1, or 0 for none, got \"{text}\"")),
+    }
+}
+
+// auto or a number of clusters
+fn parse_count(text: &str) -> Result<ClusterCount, String> {
+    if text.eq_ignore_ascii_case("auto") {
+        return Ok(ClusterCount::Auto { min: 2, max: 8 });
+    }
+    match text.parse() {
+        Ok(k) if k > 0 => Ok(ClusterCount::Fixed(k)),
+        _ => Err(format!("k must be auto or a positive number, got \"{text}\"")),
+    }
+}
+
+// column=type
+fn parse_override(text: &str) -> Result<(String, polars::prelude::DataType), String> {
+    use polars::prelude::DataType;
+
+    let (name, dtype) = text
+        .split_once('=')
+        .ok_or_else(|| format!("expected column=type, got \"{text}\""))?;
+    let dtype = match dtype.to_lowercase().as_str() {
+        "str" | "string" => DataType::String,
+        "f64" | "float" => DataType::Float64,
+        "i64" | "int" => DataType::Int64,
+        "bool" => DataType::Boolean,
+        other => return Err(format!("unknown type \"{other}\", use str, f64, i64 or bool")),
+    };
+    Ok((name.to_string(), dtype))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use polars::prelude::DataType;
+
+    #[test]
+    fn models() {
+        assert_eq!(parse_model("Linear"), Ok(Model::Linear));
+        assert_eq!(parse_model("poly2"), Ok(Model::Polynomial(2)));
+        assert_eq!(parse_model("POLY:3"), Ok(Model::Polynomial(3)));
+        assert_eq!(parse_model("exponential"), Ok(Model::Exponential));
+        assert_eq!(parse_model("log"), Ok(Model::Logarithmic));
+        for bad in ["poly", "poly0", "poly-1", "polyx", "cubic", ""] {
+            let error = parse_model(bad).unwrap_err();
+            assert!(error.contains("poly<degree>"), "{bad}: {error}");
+        }
+    }
+
+    #[test]
+    fn levels() {
+        assert_eq!(parse_level("0.9"), Ok(0.9));
+        assert_eq!(parse_level("0"), Ok(0.0)); //no bands
+        for bad in ["1", "1.5", "-0.5", "NaN", "95%"] {
+            assert!(parse_level(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(parse_count("auto"), Ok(ClusterCount::Auto { min: 2, max: 8 }));
+        assert_eq!(parse_count("AUTO"), Ok(ClusterCount::Auto { min: 2, max: 8 }));
+        assert_eq!(parse_count("4"), Ok(ClusterCount::Fixed(4)));
+        for bad in ["0", "-2", "2.5", "", "three"] {
+            assert!(parse_count(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn overrides() {
+        assert_eq!(parse_override("zip=str"), Ok(("zip".to_string(), DataType::String)));
+        assert_eq!(parse_override("hour=F64"), Ok(("hour".to_string(), DataType::Float64)));
+        assert!(parse_override("zip").is_err());
+        assert!(parse_override("day=date").unwrap_err().contains("\"date\""));
+    }
+
+    #[test]
+    fn arguments() {
+        Cli::command().debug_assert();
+
+        let fit = ["fit", "--input", "d.csv", "--x", "a", "--x", "b", "--y", "c"];
+        let fit = fit.into_iter().chain(["--model", "poly2"]);
+        let cli = Cli::try_parse_from(["app"].into_iter().chain(fit)).unwrap();
+        let Some(Command::Fit { x, model, .. }) = cli.command else {
+            panic!("expected the fit command");
+        };
+        assert_eq!(x, ["a", "b"]);
+        assert_eq!(model, Model::Polynomial(2));
+
+        let cluster = ["cluster", "--input", "d.csv", "--columns", "a,b", "--k", "3"];
+        let cli = Cli::try_parse_from(["app"].into_iter().chain(cluster)).unwrap();
+        let Some(Command::Cluster { columns, k, .. }) = cli.command else {
+            panic!("expected the cluster command");
+        };
+        assert_eq!(columns, ["a", "b"]);
+        assert_eq!(k, ClusterCount::Fixed(3));
+
+        assert!(Cli::try_parse_from(["app", "cluster", "--input", "d.csv", "--k", "0"]).is_err());
+        assert!(Cli::try_parse_from(["app"]).unwrap().command.is_none()); //runs every lesson
+    }
+}
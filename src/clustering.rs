@@ -0,0 +1,695 @@
+use linfa::prelude::*; //cargo add linfa -> for scientific operations
+use linfa_clustering::{Dbscan, GaussianMixtureModel, KMeans}; //cargo add linfa-clustering -> for KMeans, DBSCAN and GMM
+use ndarray::{Array1, Array2};
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::preprocessing::FittedPipeline;
+
+// how many clusters the k based algorithms should look for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClusterCount {
+    Fixed(usize),                    // use exactly this k
+    Auto { min: usize, max: usize }, // sweep k over min..=max and pick the best one
+}
+
+// how agglomerative clustering measures the distance between two clusters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Linkage {
+    Single,   // closest pair of points, follows chains and odd shapes
+    Complete, // furthest pair of points, compact clusters
+    Average,  // mean distance over all pairs
+    Ward,     // smallest increase in within-cluster variance, like KMeans
+}
+
+// which clustering algorithm to run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    KMeans(ClusterCount),
+    GaussianMixture(ClusterCount), // ellipse shaped clusters with their own spread
+    Agglomerative(ClusterCount, Linkage), // merges the closest clusters until k are left
+    Dbscan { tolerance: f64, min_points: usize }, // dense regions, finds k itself and marks outliers as noise
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::KMeans(_) => write!(f, "KMeans"),
+            Algorithm::GaussianMixture(_) => write!(f, "Gaussian Mixture"),
+            Algorithm::Agglomerative(_, linkage) => {
+                write!(f, "Agglomerative ({linkage:?} linkage)")
+            }
+            Algorithm::Dbscan { .. } => write!(f, "DBSCAN"),
+        }
+    }
+}
+
+// the cluster of every record, None marks a noise point
+pub type Labels = Array1<Option<usize>>;
+
+// scores of a single clustering run, used to compare different k
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KScore {
+    pub k: usize,
+    pub inertia: f64, // sum of squared distances to the cluster means, lower is tighter
+    pub silhouette: f64, // -1..1, higher means better separated clusters
+}
+
+// result of the k selection: chosen k plus the scores of every k that was tried
+#[derive(Debug, Clone, PartialEq)]
+pub struct KSelection {
+    pub k: usize,
+    pub elbow: usize, // k at the elbow of the inertia curve, for comparison with the silhouette pick
+    pub scores: Vec<KScore>,
+    pub skipped: Vec<usize>, // k the algorithm failed on during an Auto sweep, left out of the scores
+}
+
+// what a clustering run found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clustering {
+    pub selection: KSelection,
+    pub labels: Labels,
+    pub centroids: Array2<f64>, // one row per cluster, the fitted centres (KMeans, GMM) or the cluster means
+}
+
+// Runs the algorithm and returns the k selection, a label per record and the cluster centres.
+// Only DBSCAN marks noise points, and as it picks its own k its selection holds just the one score.
+pub fn cluster(records: &Array2<f64>, algorithm: &Algorithm) -> Result<Clustering> {
+    let targets: Array1<usize> = Array1::zeros(records.nrows());
+    let dataset = DatasetBase::new(records.view(), targets.view());
+
+    match *algorithm {
+        Algorithm::KMeans(count) => select_k(records, count, |k| {
+            let model = KMeans::params(k)
+                .fit(&dataset)
+                .map_err(Error::linfa("KMeans"))?;
+            Ok((model.predict(&dataset), model.centroids().clone()))
+        }),
+        Algorithm::GaussianMixture(count) => select_k(records, count, |k| {
+            let model = GaussianMixtureModel::params(k)
+                .fit(&dataset)
+                .map_err(Error::linfa("Gaussian mixture"))?;
+            Ok((model.predict(&dataset), model.means().clone()))
+        }),
+        Algorithm::Agglomerative(count, linkage) => select_k(records, count, |k| {
+            let labels = agglomerative(records, k, linkage);
+            let centroids = cluster_means(records, &labels.mapv(Some));
+            Ok((labels, centroids))
+        }),
+        Algorithm::Dbscan {
+            tolerance,
+            min_points,
+        } => {
+            let labels = Dbscan::params(min_points)
+                .tolerance(tolerance)
+                .check()
+                .map_err(Error::linfa("DBSCAN"))?
+                .transform(records);
+            let k = labels.iter().flatten().max().map_or(0, |m| m + 1);
+            let score = KScore {
+                k,
+                inertia: inertia(records, &labels),
+                silhouette: silhouette_score(records, &labels),
+            };
+            let selection = KSelection {
+                k,
+                elbow: k,
+                scores: vec![score],
+                skipped: Vec::new(),
+            };
+            let centroids = cluster_means(records, &labels);
+            Ok(Clustering {
+                selection,
+                labels,
+                centroids,
+            })
+        }
+    }
+}
+
+// Quality of a clustering, with the labels mapped back onto the rows of the DataFrame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterReport {
+    pub algorithm: Algorithm,
+    pub selection: KSelection,
+    pub labels: Vec<Option<usize>>, // one per DataFrame row, None for noise and rows left out for nulls
+    pub distances: Vec<Option<f64>>, // distance of every DataFrame row to its cluster centre, None like above
+    pub centroids: Array2<f64>,
+    pub sizes: Vec<usize>, // points per cluster
+    pub noise: usize,
+    pub dropped: usize, // rows left out because of nulls
+    pub imputed: usize, // nulls filled in with the column's mean or median
+    pub inertia: f64,
+    pub silhouette: f64,
+    pub davies_bouldin: f64, // >= 0, lower means tighter and better separated clusters
+    pub calinski_harabasz: f64, // >= 0, higher means tighter and better separated clusters
+    pub preprocessing: Option<FittedPipeline>, // how the columns were rescaled before clustering
+    pub explained_variance: Option<f64>, // share of the variance the two PCA components keep, when plotted that way
+}
+
+impl ClusterReport {
+    // `rows` holds the DataFrame row of every record, `height` the number of rows in the DataFrame
+    pub fn new(
+        records: &Array2<f64>,
+        clustering: &Clustering,
+        algorithm: &Algorithm,
+        rows: &[usize],
+        height: usize,
+    ) -> ClusterReport {
+        let labels = &clustering.labels;
+        let centroids = &clustering.centroids;
+        let mut row_labels = vec![None; height];
+        let mut distances = vec![None; height];
+        for ((&row, &label), record) in rows.iter().zip(labels).zip(records.rows()) {
+            row_labels[row] = label;
+            distances[row] = label.map(|label| {
+                (&record - &centroids.row(label))
+                    .mapv(|v| v * v)
+                    .sum()
+                    .sqrt()
+            });
+        }
+
+        let mut sizes = vec![0; clustering.centroids.nrows()];
+        for label in labels.iter().flatten() {
+            sizes[*label] += 1;
+        }
+
+        ClusterReport {
+            algorithm: *algorithm,
+            selection: clustering.selection.clone(),
+            labels: row_labels,
+            distances,
+            centroids: clustering.centroids.clone(),
+            sizes,
+            noise: labels.iter().filter(|label| label.is_none()).count(),
+            dropped: height - rows.len(),
+            imputed: 0,
+            inertia: inertia(records, labels),
+            silhouette: silhouette_score(records, labels),
+            davies_bouldin: davies_bouldin(records, labels),
+            calinski_harabasz: calinski_harabasz(records, labels),
+            preprocessing: None,
+            explained_variance: None,
+        }
+    }
+}
+
+impl fmt::Display for ClusterReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} clustering: k = {}", self.algorithm, self.selection.k)?;
+        for (cluster, (centroid, size)) in self
+            .centroids
+            .rows()
+            .into_iter()
+            .zip(&self.sizes)
+            .enumerate()
+        {
+            let centre: Vec<String> = centroid.iter().map(|v| format!("{v:.3}")).collect();
+            writeln!(
+                f,
+                "  cluster {cluster}: {size} points around ({})",
+                centre.join(", ")
+            )?;
+        }
+        if self.noise > 0 || self.dropped > 0 || self.imputed > 0 {
+            writeln!(
+                f,
+                "  noise: {} points, dropped: {} rows, imputed: {} values",
+                self.noise, self.dropped, self.imputed
+            )?;
+        }
+        if let Some(explained) = self.explained_variance {
+            writeln!(f, "  PCA: the two components explain {:.1}% of the variance", 100.0 * explained)?;
+        }
+        write!(
+            f,
+            "  inertia = {:.3}, silhouette = {:.3}, Davies-Bouldin = {:.3}, Calinski-Harabasz = {:.3}",
+            self.inertia, self.silhouette, self.davies_bouldin, self.calinski_harabasz
+        )
+    }
+}
+
+// Fits every k the count allows and keeps the labels and centres of the best one.
+// In Auto mode the k with the best silhouette score wins,
+// the elbow of the inertia curve is used as a tie breaker (and when silhouette can't tell them apart).
+// A k the algorithm fails on (e.g. a singular GMM covariance) is skipped, only if every k fails
+// is that an error, the one from the last k tried.
+fn select_k(
+    records: &Array2<f64>,
+    count: ClusterCount,
+    fit: impl Fn(usize) -> Result<(Array1<usize>, Array2<f64>)>,
+) -> Result<Clustering> {
+    let n = records.nrows();
+    let (min, max) = match count {
+        ClusterCount::Fixed(k) => (k, k),
+        ClusterCount::Auto { min, max } => (min.max(2), max.min(n.saturating_sub(1))), //silhouette needs 2 <= k < n
+    };
+    if min == 0 || min > max || max > n {
+        return Err(Error::invalid(format!(
+            "cannot pick {min}..={max} clusters from {n} points"
+        )));
+    }
+
+    let mut fits = Vec::new();
+    let mut skipped = Vec::new();
+    let mut last_error = None;
+    for k in min..=max {
+        let (labels, centroids) = match fit(k) {
+            Ok(fitted) => fitted,
+            Err(e) => {
+                skipped.push(k);
+                last_error = Some(e);
+                continue;
+            }
+        };
+        let labels: Labels = labels.mapv(Some);
+        let score = KScore {
+            k,
+            inertia: inertia(records, &labels),
+            silhouette: silhouette_score(records, &labels),
+        };
+        fits.push((score, labels, centroids));
+    }
+    if fits.is_empty() {
+        return Err(last_error.unwrap_or_else(|| Error::invalid("no k to try")));
+    }
+
+    let scores: Vec<KScore> = fits.iter().map(|(score, _, _)| *score).collect();
+    let elbow = elbow(&scores);
+    let best = scores
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| {
+            a.silhouette
+                .total_cmp(&b.silhouette)
+                .then_with(|| (a.k == elbow).cmp(&(b.k == elbow))) //prefer the elbow on a tie
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+
+    let (score, labels, centroids) = fits.swap_remove(best);
+    let selection = KSelection {
+        k: score.k,
+        elbow,
+        scores,
+        skipped,
+    };
+    Ok(Clustering {
+        selection,
+        labels,
+        centroids,
+    })
+}
+
+// Bottom-up clustering: every point starts as its own cluster and the two closest clusters are merged
+// until k are left. Distances between clusters are updated with the Lance-Williams formula, O(n³) overall
+// which is fine for the small DataFrames here.
+fn agglomerative(records: &Array2<f64>, k: usize, linkage: Linkage) -> Array1<usize> {
+    let n = records.nrows();
+    let squared = |i: usize, j: usize| (&records.row(i) - &records.row(j)).mapv(|v| v * v).sum();
+
+    // Ward works on squared distances, the other linkages on plain ones
+    let mut distance = Array2::<f64>::zeros((n, n));
+    for i in 0..n {
+        for j in 0..i {
+            let d = match linkage {
+                Linkage::Ward => squared(i, j),
+                _ => squared(i, j).sqrt(),
+            };
+            distance[(i, j)] = d;
+            distance[(j, i)] = d;
+        }
+    }
+
+    let mut members: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let mut alive: Vec<usize> = (0..n).collect(); //clusters that haven't been merged away
+
+    while alive.len() > k.max(1) {
+        // closest pair of live clusters, a < b in alive order
+        let mut closest = (0, 1, f64::INFINITY);
+        for (ai, &a) in alive.iter().enumerate() {
+            for &b in &alive[ai + 1..] {
+                if distance[(a, b)] < closest.2 {
+                    closest = (a, b, distance[(a, b)]);
+                }
+            }
+        }
+        let (a, b, d_ab) = closest;
+
+        // distance from the merged cluster (kept in slot a) to every other live cluster
+        let (n_a, n_b) = (members[a].len() as f64, members[b].len() as f64);
+        for &c in alive.iter().filter(|&&c| c != a && c != b) {
+            let (d_ac, d_bc) = (distance[(a, c)], distance[(b, c)]);
+            let n_c = members[c].len() as f64;
+            let d = match linkage {
+                Linkage::Single => d_ac.min(d_bc),
+                Linkage::Complete => d_ac.max(d_bc),
+                Linkage::Average => (n_a * d_ac + n_b * d_bc) / (n_a + n_b),
+                Linkage::Ward => {
+                    ((n_a + n_c) * d_ac + (n_b + n_c) * d_bc - n_c * d_ab) / (n_a + n_b + n_c)
+                }
+            };
+            distance[(a, c)] = d;
+            distance[(c, a)] = d;
+        }
+
+        let merged = std::mem::take(&mut members[b]);
+        members[a].extend(merged);
+        alive.retain(|&c| c != b);
+    }
+
+    let mut labels = Array1::zeros(n);
+    for (label, &cluster) in alive.iter().enumerate() {
+        for &point in &members[cluster] {
+            labels[point] = label;
+        }
+    }
+    labels
+}
+
+// Mean of the (non-noise) points of every cluster, one row per cluster.
+fn cluster_means(records: &Array2<f64>, labels: &Labels) -> Array2<f64> {
+    let k = labels.iter().flatten().max().map_or(0, |m| m + 1);
+    let mut means = Array2::<f64>::zeros((k, records.ncols()));
+    let mut counts = vec![0usize; k];
+    for (row, label) in records.rows().into_iter().zip(labels) {
+        if let Some(label) = *label {
+            let mut sum = means.row_mut(label);
+            sum += &row;
+            counts[label] += 1;
+        }
+    }
+    for (mut mean, count) in means.rows_mut().into_iter().zip(counts) {
+        mean /= count.max(1) as f64;
+    }
+    means
+}
+
+// Sum of squared distances from every (non-noise) point to the mean of its cluster.
+fn inertia(records: &Array2<f64>, labels: &Labels) -> f64 {
+    let means = cluster_means(records, labels);
+    records
+        .rows()
+        .into_iter()
+        .zip(labels)
+        .filter_map(|(row, label)| {
+            let mean = means.row((*label)?);
+            Some((&row - &mean).mapv(|v| v * v).sum())
+        })
+        .sum()
+}
+
+// Davies-Bouldin index: for every cluster the worst ratio (s_i + s_j) / d(c_i, c_j) against another cluster,
+// averaged, where s is the mean distance of a cluster's points to its mean and d the distance between means.
+pub fn davies_bouldin(records: &Array2<f64>, labels: &Labels) -> f64 {
+    let means = cluster_means(records, labels);
+    let k = means.nrows();
+    if k < 2 {
+        return 0.0;
+    }
+
+    let mut spread = vec![0.0; k];
+    let mut counts = vec![0usize; k];
+    for (row, label) in records.rows().into_iter().zip(labels) {
+        if let Some(label) = *label {
+            spread[label] += (&row - &means.row(label)).mapv(|v| v * v).sum().sqrt();
+            counts[label] += 1;
+        }
+    }
+    for (s, &count) in spread.iter_mut().zip(&counts) {
+        *s /= count.max(1) as f64;
+    }
+
+    let worst = |i: usize| {
+        (0..k)
+            .filter(|&j| j != i)
+            .map(|j| {
+                let distance = (&means.row(i) - &means.row(j)).mapv(|v| v * v).sum().sqrt();
+                (spread[i] + spread[j]) / distance
+            })
+            .fold(0.0, f64::max)
+    };
+    (0..k).map(worst).sum::<f64>() / k as f64
+}
+
+// Calinski-Harabasz index: between-cluster over within-cluster dispersion, each divided by its
+// degrees of freedom, (B / (k - 1)) / (W / (n - k)).
+pub fn calinski_harabasz(records: &Array2<f64>, labels: &Labels) -> f64 {
+    let means = cluster_means(records, labels);
+    let k = means.nrows();
+    let clustered: Vec<usize> = labels.iter().flatten().copied().collect();
+    let n = clustered.len();
+    if k < 2 || n <= k {
+        return 0.0;
+    }
+
+    // mean of all clustered points
+    let mut centre = Array1::<f64>::zeros(records.ncols());
+    for (row, label) in records.rows().into_iter().zip(labels) {
+        if label.is_some() {
+            centre += &row;
+        }
+    }
+    centre /= n as f64;
+
+    let mut sizes = vec![0usize; k];
+    for &label in &clustered {
+        sizes[label] += 1;
+    }
+    let between: f64 = means
+        .rows()
+        .into_iter()
+        .zip(&sizes)
+        .map(|(mean, &size)| size as f64 * (&mean - &centre).mapv(|v| v * v).sum())
+        .sum();
+    let within = inertia(records, labels);
+    if within == 0.0 {
+        return f64::INFINITY; //every point sits on its cluster mean
+    }
+    (between / (k - 1) as f64) / (within / (n - k) as f64)
+}
+
+// Mean silhouette over all points: for each point compare the mean distance to its own cluster (a)
+// with the mean distance to the nearest other cluster (b), s = (b - a) / max(a, b).
+// Noise points (label None) are left out on both sides.
+pub fn silhouette_score(records: &Array2<f64>, labels: &Labels) -> f64 {
+    let clustered: Vec<(usize, usize)> = labels
+        .iter()
+        .enumerate()
+        .filter_map(|(i, label)| Some((i, (*label)?)))
+        .collect();
+    let n = clustered.len();
+    let k = clustered
+        .iter()
+        .map(|&(_, label)| label)
+        .max()
+        .map_or(0, |m| m + 1);
+    if n < 2 || k < 2 {
+        return 0.0;
+    }
+
+    let mut total = 0.0;
+    for &(i, own) in &clustered {
+        let mut sums = vec![0.0; k];
+        let mut counts = vec![0usize; k];
+        for &(j, label) in &clustered {
+            if i == j {
+                continue;
+            }
+            let d = (&records.row(i) - &records.row(j))
+                .mapv(|v| v * v)
+                .sum()
+                .sqrt();
+            sums[label] += d;
+            counts[label] += 1;
+        }
+
+        if counts[own] == 0 {
+            continue; //singleton clusters score 0 by convention
+        }
+        let a = sums[own] / counts[own] as f64;
+        let b = (0..k)
+            .filter(|&c| c != own && counts[c] > 0)
+            .map(|c| sums[c] / counts[c] as f64)
+            .fold(f64::INFINITY, f64::min);
+        if b.is_finite() && a.max(b) > 0.0 {
+            total += (b - a) / a.max(b);
+        }
+    }
+    total / n as f64
+}
+
+// Elbow of the inertia curve: with k and inertia both rescaled to 0..1, the k whose point lies
+// furthest from the straight line joining the first and last points.
+fn elbow(scores: &[KScore]) -> usize {
+    let (Some(first), Some(last)) = (scores.first(), scores.last()) else {
+        return 0;
+    };
+    let k_span = (last.k - first.k) as f64;
+    let inertia_span = first.inertia - last.inertia;
+    if scores.len() < 3 || inertia_span <= 0.0 {
+        return first.k;
+    }
+
+    scores
+        .iter()
+        .map(|s| {
+            let x = (s.k - first.k) as f64 / k_span; //0 at the first k, 1 at the last
+            let y = (first.inertia - s.inertia) / inertia_span; //0 at the first k, 1 at the last
+            (s.k, y - x) //distance above the diagonal, largest at the knee
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(first.k, |(k, _)| k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    // three tight groups of four points, far apart
+    fn blobs() -> Array2<f64> {
+        let mut points = Vec::new();
+        for (cx, cy) in [(0.0, 0.0), (10.0, 0.0), (5.0, 9.0)] {
+            for (dx, dy) in [(0.0, 0.0), (0.3, 0.1), (0.1, 0.3), (0.2, 0.2)] {
+                points.extend([cx + dx, cy + dy]);
+            }
+        }
+        Array2::from_shape_vec((12, 2), points).unwrap()
+    }
+
+    #[test]
+    fn auto_finds_the_separated_groups() {
+        let records = blobs();
+        let auto = ClusterCount::Auto { min: 2, max: 6 };
+        for algorithm in [
+            Algorithm::KMeans(auto),
+            Algorithm::Agglomerative(auto, Linkage::Ward),
+        ] {
+            let clustering = cluster(&records, &algorithm).unwrap();
+            let selection = &clustering.selection;
+            assert_eq!(selection.k, 3, "{algorithm}");
+            assert_eq!(selection.elbow, 3, "{algorithm}");
+            assert_eq!(selection.scores.len(), 5);
+            assert!(selection.skipped.is_empty());
+            // every group ends up in one cluster
+            for group in clustering.labels.exact_chunks(4) {
+                assert!(group.iter().all(|label| *label == group[0]));
+            }
+        }
+    }
+
+    #[test]
+    fn quality_metrics() {
+        // two clusters {0, 2} and {10, 12} on a line, means 1 and 11, and a noise point
+        let records = array![[0.0], [2.0], [10.0], [12.0], [100.0]];
+        let labels = array![Some(0), Some(0), Some(1), Some(1), None];
+
+        // each cluster spreads 1 around its mean, the means are 10 apart: (1 + 1) / 10
+        assert!((davies_bouldin(&records, &labels) - 0.2).abs() < 1e-12);
+        // B = 2 * 5² + 2 * 5² = 100 over k - 1 = 1, W = 4 * 1² = 4 over n - k = 2
+        assert!((calinski_harabasz(&records, &labels) - 50.0).abs() < 1e-12);
+        // the outer points have a = 2, b = 11, the inner ones a = 2, b = 9
+        let silhouette = (9.0 / 11.0 + 7.0 / 9.0) / 2.0;
+        assert!((silhouette_score(&records, &labels) - silhouette).abs() < 1e-12);
+        assert_eq!(inertia(&records, &labels), 4.0);
+
+        // a single cluster has nothing to be separated from
+        let one = array![Some(0), Some(0), Some(0), Some(0), None];
+        assert_eq!(davies_bouldin(&records, &one), 0.0);
+        assert_eq!(calinski_harabasz(&records, &one), 0.0);
+        assert_eq!(silhouette_score(&records, &one), 0.0);
+        // every point on its cluster mean, nothing within to divide by
+        let stacked = array![[0.0], [0.0], [5.0], [5.0]];
+        let pairs = array![Some(0), Some(0), Some(1), Some(1)];
+        assert_eq!(calinski_harabasz(&stacked, &pairs), f64::INFINITY);
+        assert_eq!(davies_bouldin(&stacked, &pairs), 0.0);
+    }
+
+    #[test]
+    fn dbscan_marks_outliers_as_noise() {
+        // the three groups and a point far away from all of them
+        let mut records = blobs();
+        records.push_row(ndarray::ArrayView1::from(&[50.0, 50.0])).unwrap();
+        let algorithm = Algorithm::Dbscan {
+            tolerance: 1.0,
+            min_points: 2,
+        };
+        let clustering = cluster(&records, &algorithm).unwrap();
+        assert_eq!(clustering.labels[12], None);
+        assert_eq!(clustering.selection.k, 3); //from the dense groups, nothing to choose
+        assert_eq!(clustering.centroids.nrows(), 3);
+        for group in clustering.labels.slice(ndarray::s![..12]).exact_chunks(4) {
+            assert!(group[0].is_some() && group.iter().all(|label| *label == group[0]));
+        }
+        // the noise point stays out of the centres
+        let first = clustering.labels[0].unwrap();
+        assert!((clustering.centroids[(first, 0)] - 0.15).abs() < 1e-12);
+
+        let rows: Vec<usize> = (0..13).collect();
+        let report = ClusterReport::new(&records, &clustering, &algorithm, &rows, 13);
+        assert_eq!((report.noise, report.sizes.as_slice()), (1, &[4, 4, 4][..]));
+
+        // nothing is dense enough: every point is noise, no clusters at all
+        let sparse = Algorithm::Dbscan {
+            tolerance: 0.01,
+            min_points: 2,
+        };
+        let clustering = cluster(&records, &sparse).unwrap();
+        assert!(clustering.labels.iter().all(Option::is_none));
+        assert_eq!(clustering.selection.k, 0);
+    }
+
+    #[test]
+    fn elbow_of_the_inertia_curve() {
+        let scores: Vec<KScore> = [(2, 100.0), (3, 20.0), (4, 15.0), (5, 12.0)]
+            .into_iter()
+            .map(|(k, inertia)| KScore {
+                k,
+                inertia,
+                silhouette: 0.0,
+            })
+            .collect();
+        assert_eq!(elbow(&scores), 3);
+        assert_eq!(elbow(&scores[..2]), 2); //too few points for a knee
+        assert_eq!(elbow(&[]), 0);
+    }
+
+    #[test]
+    fn failing_k_is_skipped() {
+        let records = blobs();
+        let means = |labels: &Array1<usize>| cluster_means(&records, &labels.mapv(Some));
+        let fit = |k: usize| {
+            if k == 3 {
+                return Err(Error::invalid("singular covariance"));
+            }
+            let labels = agglomerative(&records, k, Linkage::Average);
+            let centroids = means(&labels);
+            Ok((labels, centroids))
+        };
+        let clustering = select_k(&records, ClusterCount::Auto { min: 2, max: 4 }, fit).unwrap();
+        assert_eq!(clustering.selection.skipped, [3]);
+        assert_eq!(clustering.selection.scores.len(), 2);
+
+        // a fixed k, or every k of the sweep failing, is still an error
+        assert!(select_k(&records, ClusterCount::Fixed(3), fit).is_err());
+        let failing = |_| -> Result<(Array1<usize>, Array2<f64>)> { Err(Error::invalid("no")) };
+        let error = select_k(&records, ClusterCount::Auto { min: 2, max: 4 }, failing).unwrap_err();
+        assert_eq!(error.code(), "invalid_parameter");
+
+        // more k than distinct points: the sweep keeps whatever k worked
+        let duplicates = array![[0.0, 0.0], [0.0, 0.0], [0.0, 0.0], [5.0, 5.0], [5.0, 5.0], [5.0, 5.0]];
+        for algorithm in [
+            Algorithm::KMeans(ClusterCount::Auto { min: 2, max: 5 }),
+            Algorithm::GaussianMixture(ClusterCount::Auto { min: 2, max: 5 }),
+        ] {
+            let clustering = cluster(&duplicates, &algorithm).unwrap();
+            assert_eq!(clustering.labels[0], clustering.labels[2], "{algorithm}");
+            assert_ne!(clustering.labels[0], clustering.labels[3], "{algorithm}");
+        }
+
+        let two = array![[0.0, 0.0], [1.0, 1.0]];
+        assert!(cluster(&two, &Algorithm::KMeans(ClusterCount::Fixed(3))).is_err());
+    }
+}
@@ -0,0 +1,368 @@
+use ndarray::Array2; //cargo add ndarray@0.15 -> linfa breaks with 0.16
+use polars::prelude::*; //cargo add polars --features lazy,ndarray,csv,parquet,json,ipc -> for dataframes and files
+use std::fs::File;
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::loader::LoadError;
+
+// Gets a column as f64, if the name is wrong or the type doesn't fit the error lists what the DataFrame does have.
+pub fn f64_column<'a>(df: &'a DataFrame, name: &str) -> Result<&'a Float64Chunked> {
+    let column = df.column(name).map_err(|_| {
+        Error::column(
+            name,
+            format!("not found, available columns: {}", column_list(df, |_| true)),
+        )
+    })?;
+
+    column.f64().map_err(|_| {
+        Error::column(
+            name,
+            format!(
+                "has type {}, expected f64 (f64 columns: {})",
+                column.dtype(),
+                column_list(df, |dtype| *dtype == DataType::Float64)
+            ),
+        )
+    })
+}
+
+// Gets several f64 columns at once, in the order they were asked for.
+pub fn f64_columns<'a>(
+    df: &'a DataFrame,
+    names: &[&str],
+) -> Result<Vec<&'a Float64Chunked>> {
+    if names.is_empty() {
+        return Err(Error::invalid("no columns given"));
+    }
+    names.iter().map(|name| f64_column(df, name)).collect()
+}
+
+// comma separated names of the columns whose type passes the filter
+fn column_list(df: &DataFrame, filter: impl Fn(&DataType) -> bool) -> String {
+    let names: Vec<String> = df
+        .get_columns()
+        .iter()
+        .filter(|c| filter(c.dtype()))
+        .map(|c| c.name().to_string())
+        .collect();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+// What to do with rows that have a null in one of the columns being used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullPolicy {
+    #[default]
+    Drop, // leave the row out
+    Mean,   // fill the null with the mean of the column's other values
+    Median, // fill the null with the median of the column's other values
+    Error,  // refuse to go on
+}
+
+// The used columns as one row per record, after the null policy was applied.
+#[derive(Debug, Clone)]
+pub struct Records {
+    pub values: Array2<f64>, // one column per name, in the order they were asked for
+    pub rows: Vec<usize>,    // row index in the DataFrame of every record
+    pub dropped: usize,      // rows left out by NullPolicy::Drop
+    pub imputed: usize,      // values filled in by NullPolicy::Mean / Median
+}
+
+// Reads the f64 columns row by row, nulls are handled as the policy says.
+pub fn records(
+    df: &DataFrame,
+    names: &[&str],
+    policy: NullPolicy,
+) -> Result<Records> {
+    let columns = f64_columns(df, names)?;
+
+    // value to put in place of a null, one per column
+    let fill: Vec<Option<f64>> = columns
+        .iter()
+        .zip(names)
+        .map(|(column, name)| {
+            let value = match policy {
+                NullPolicy::Mean => column.mean(),
+                NullPolicy::Median => column.median(),
+                NullPolicy::Drop | NullPolicy::Error => return Ok(None),
+            };
+            match value {
+                Some(value) => Ok(Some(value)),
+                None => Err(Error::column(*name, "has no values to impute from")),
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut flat = Vec::new();
+    let mut rows = Vec::new();
+    let (mut dropped, mut imputed) = (0, 0);
+    'rows: for row in 0..df.height() {
+        let mut values = Vec::with_capacity(columns.len());
+        for (i, column) in columns.iter().enumerate() {
+            match (column.get(row), fill[i]) {
+                (Some(value), _) => values.push(value),
+                (None, Some(value)) => {
+                    values.push(value);
+                    imputed += 1;
+                }
+                (None, None) if policy == NullPolicy::Error => {
+                    return Err(Error::column(names[i], format!("is null in row {row}")));
+                }
+                (None, None) => {
+                    dropped += 1;
+                    continue 'rows;
+                }
+            }
+        }
+        flat.extend(values);
+        rows.push(row);
+    }
+
+    Ok(Records {
+        values: Array2::from_shape_vec((rows.len(), columns.len()), flat)?,
+        rows,
+        dropped,
+        imputed,
+    })
+}
+
+// File formats load_dataframe can read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Csv,
+    Parquet,
+    Json,   // one JSON array of records
+    NdJson, // one JSON record per line
+    Ipc,    // Arrow IPC / Feather v2
+}
+
+impl FileFormat {
+    // guesses the format from the file extension
+    pub fn from_path(path: &Path) -> Option<FileFormat> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" | "tsv" | "txt" => Some(FileFormat::Csv),
+            "parquet" | "pq" => Some(FileFormat::Parquet),
+            "json" => Some(FileFormat::Json),
+            "ndjson" | "jsonl" => Some(FileFormat::NdJson),
+            "ipc" | "arrow" | "feather" => Some(FileFormat::Ipc),
+            _ => None,
+        }
+    }
+}
+
+// How load_dataframe reads a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadOptions {
+    pub format: Option<FileFormat>, // None picks it from the extension
+    pub infer_schema_rows: Option<usize>, // rows looked at to guess column types (CSV, JSON), None reads them all
+    pub schema_overrides: Vec<(String, DataType)>, // forced column types, e.g. ("zip", DataType::String)
+    pub separator: u8,                             // CSV only
+    pub has_header: bool,                          // CSV only
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            format: None,
+            infer_schema_rows: Some(100),
+            schema_overrides: Vec::new(),
+            separator: b',',
+            has_header: true,
+        }
+    }
+}
+
+// Reads a CSV, Parquet, JSON, NDJSON or IPC file into a DataFrame. Column types are inferred
+// (or taken from the file for Parquet and IPC) and then replaced by any schema overrides.
+pub fn load_dataframe(
+    path: impl AsRef<Path>,
+    options: &LoadOptions,
+) -> Result<DataFrame> {
+    let path = path.as_ref();
+    let format = match options.format.or_else(|| FileFormat::from_path(path)) {
+        Some(format) => format,
+        None => {
+            return Err(Error::invalid(format!(
+                "cannot tell the format of {} from its extension, set LoadOptions::format",
+                path.display()
+            )));
+        }
+    };
+    let open = || File::open(path).map_err(|e| LoadError::from_io(path, e));
+
+    let overrides: Schema = options
+        .schema_overrides
+        .iter()
+        .map(|(name, dtype)| Field::new(name.into(), dtype.clone()))
+        .collect();
+
+    let df = match format {
+        FileFormat::Csv => CsvReadOptions::default()
+            .with_has_header(options.has_header)
+            .with_infer_schema_length(options.infer_schema_rows)
+            .with_schema_overwrite(Some(Arc::new(overrides.clone())))
+            .map_parse_options(|parse| parse.with_separator(options.separator))
+            .into_reader_with_file_handle(open()?)
+            .finish()?,
+        FileFormat::Json | FileFormat::NdJson => {
+            let json_format = match format {
+                FileFormat::NdJson => JsonFormat::JsonLines,
+                _ => JsonFormat::Json,
+            };
+            JsonReader::new(open()?)
+                .with_json_format(json_format)
+                .infer_schema_len(options.infer_schema_rows.and_then(NonZeroUsize::new))
+                .with_schema_overwrite(&overrides)
+                .finish()?
+        }
+        FileFormat::Parquet => ParquetReader::new(open()?).finish()?,
+        FileFormat::Ipc => IpcReader::new(open()?).finish()?,
+    };
+
+    // Parquet and IPC carry their own schema, the overrides become casts afterwards
+    // (for CSV and JSON this is a no-op as the readers already used them)
+    let mut df = df;
+    for (name, dtype) in &options.schema_overrides {
+        let column = df.column(name).map_err(|_| {
+            Error::column(
+                name,
+                format!("has a schema override but {} has no such column", path.display()),
+            )
+        })?;
+        if column.dtype() != dtype {
+            let cast = column.cast(dtype)?;
+            df.with_column(cast)?;
+        }
+    }
+    Ok(df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::{self, Algorithm, ClusterCount, ClusterReport};
+    use crate::loader::test_files::TempFile;
+
+    // two groups, x around 0 or 10, with a null in x in row 1 and in y in row 4
+    fn with_nulls() -> DataFrame {
+        df![
+            "x" => [Some(0.0), None, Some(0.2), Some(10.0), Some(10.1), Some(9.9), Some(0.1)],
+            "y" => [Some(1.0), Some(1.1), Some(0.9), Some(5.0), None, Some(5.1), Some(1.2)]
+        ]
+        .unwrap()
+    }
+
+    #[test]
+    fn null_policies_keep_rows_in_line() {
+        let df = with_nulls();
+
+        let dropped = records(&df, &["x", "y"], NullPolicy::Drop).unwrap();
+        assert_eq!(dropped.rows, [0, 2, 3, 5, 6]);
+        assert_eq!((dropped.dropped, dropped.imputed), (2, 0));
+        // every record holds the values of the row it says it came from
+        for (record, &row) in dropped.values.rows().into_iter().zip(&dropped.rows) {
+            assert_eq!(record[0], df.column("x").unwrap().f64().unwrap().get(row).unwrap());
+        }
+
+        let mean = records(&df, &["x", "y"], NullPolicy::Mean).unwrap();
+        assert_eq!(mean.rows, (0..7).collect::<Vec<_>>());
+        assert_eq!((mean.dropped, mean.imputed), (0, 2));
+        assert!((mean.values[(1, 0)] - 30.3 / 6.0).abs() < 1e-9); //mean of the other x
+        assert_eq!(mean.values[(1, 1)], 1.1);
+        assert!((mean.values[(4, 1)] - 14.3 / 6.0).abs() < 1e-9);
+
+        let median = records(&df, &["x", "y"], NullPolicy::Median).unwrap();
+        assert_eq!((median.dropped, median.imputed), (0, 2));
+        assert!((median.values[(1, 0)] - 5.05).abs() < 1e-9); //halfway between 0.2 and 9.9
+        assert!((median.values[(4, 1)] - 1.15).abs() < 1e-9);
+
+        let error = records(&df, &["x", "y"], NullPolicy::Error).unwrap_err();
+        assert_eq!(error.to_string(), "column \"x\" is null in row 1");
+    }
+
+    #[test]
+    fn labels_map_back_to_their_rows() {
+        let df = with_nulls();
+        let records = records(&df, &["x", "y"], NullPolicy::Drop).unwrap();
+        let algorithm = Algorithm::KMeans(ClusterCount::Fixed(2));
+        let clustering = clustering::cluster(&records.values, &algorithm).unwrap();
+        let report =
+            ClusterReport::new(&records.values, &clustering, &algorithm, &records.rows, df.height());
+
+        let labels = &report.labels;
+        assert_eq!(labels.len(), 7);
+        assert_eq!((labels[1], labels[4]), (None, None)); //the rows with a null
+        assert!(labels[0].is_some() && labels[0] == labels[2] && labels[0] == labels[6]);
+        assert!(labels[3].is_some() && labels[3] == labels[5]);
+        assert_ne!(labels[0], labels[3]);
+        assert_eq!(report.dropped, 2);
+    }
+
+    #[test]
+    fn csv_with_schema_overrides() {
+        // inferred, the sensor ids lose their leading zeros and hour is an integer
+        let inferred = load_dataframe("data/measurements.csv", &LoadOptions::default()).unwrap();
+        assert_eq!(inferred.column("sensor").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(inferred.column("hour").unwrap().dtype(), &DataType::Int64);
+
+        let options = LoadOptions {
+            schema_overrides: vec![
+                ("sensor".to_string(), DataType::String),
+                ("hour".to_string(), DataType::Float64),
+            ],
+            ..LoadOptions::default()
+        };
+        let df = load_dataframe("data/measurements.csv", &options).unwrap();
+        assert_eq!(df.height(), inferred.height());
+        assert_eq!(df.column("sensor").unwrap().str().unwrap().get(0), Some("00417"));
+        assert_eq!(f64_column(&df, "hour").unwrap().get(1), Some(2.0));
+        assert_eq!(df.column("temperature").unwrap().dtype(), &DataType::Float64);
+
+        let options = LoadOptions {
+            schema_overrides: vec![("pressure".to_string(), DataType::Float64)],
+            ..LoadOptions::default()
+        };
+        let error = load_dataframe("data/measurements.csv", &options).unwrap_err();
+        assert_eq!(error.code(), "column");
+    }
+
+    #[test]
+    fn other_formats_and_failures() {
+        // semicolons, no header, and the format given because of the extension
+        let file = TempFile::new("values.dat", b"1;a\n2;b\n");
+        let options = LoadOptions {
+            format: Some(FileFormat::Csv),
+            separator: b';',
+            has_header: false,
+            ..LoadOptions::default()
+        };
+        let df = load_dataframe(&file.0, &options).unwrap();
+        assert_eq!(df.shape(), (2, 2));
+        assert!(load_dataframe(&file.0, &LoadOptions::default()).is_err()); //.dat says nothing
+
+        let lines = b"{\"x\": 1, \"y\": 2.5}\n{\"x\": 3, \"y\": 4.5}\n";
+        let file = TempFile::new("values.jsonl", lines);
+        let options = LoadOptions {
+            schema_overrides: vec![("x".to_string(), DataType::Float64)],
+            ..LoadOptions::default()
+        };
+        let df = load_dataframe(&file.0, &options).unwrap();
+        assert_eq!(f64_column(&df, "x").unwrap().get(1), Some(3.0));
+
+        // Parquet keeps its own schema, the override becomes a cast
+        let mut written = df!["x" => [1i64, 2], "y" => [0.5, 1.5]].unwrap();
+        let file = TempFile::new("values.parquet", b"");
+        ParquetWriter::new(File::create(&file.0).unwrap()).finish(&mut written).unwrap();
+        let df = load_dataframe(&file.0, &options).unwrap();
+        assert_eq!(f64_column(&df, "x").unwrap().get(0), Some(1.0));
+
+        let missing = load_dataframe("data/no-such-file.csv", &LoadOptions::default()).unwrap_err();
+        assert_eq!(missing.code(), "load.not_found");
+    }
+}
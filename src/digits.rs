@@ -0,0 +1,172 @@
+// Reversing the digits of an integer, 123 -> 321, -120 -> -21, arithmetically instead of
+// through a String: take the last digit off with % and push it onto the result with * radix + digit.
+// Works for every integer width and any radix, and says so when the result doesn't fit the type.
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DigitsError {
+    // a radix below 2 or too big for the integer type, e.g. 300 for a u8
+    #[error("radix {radix} is not supported for {width}, use 2 to {max}")]
+    InvalidRadix {
+        radix: u32,
+        max: u32,            // the largest radix the type can hold, u32::MAX for the wide types
+        width: &'static str, // the type name, like in Overflow
+    },
+    // the reversed number is too big for the type, e.g. 1_999_999_999 -> 9_999_999_991 in an i32
+    #[error("reversing the base {radix} digits of {value} overflows {width}")]
+    Overflow {
+        value: String, // the number that was reversed, as text so every width fits
+        radix: u32,
+        width: &'static str, // the type name, i32, u64...
+    },
+}
+
+// implemented for every primitive integer by the macro below
+pub trait ReverseDigits: Sized {
+    fn reverse_digits(self, radix: u32) -> Result<Self, DigitsError>;
+}
+
+// so callers can write reverse_digits(x, 10) as well as x.reverse_digits(10)
+pub fn reverse_digits<T: ReverseDigits>(value: T, radix: u32) -> Result<T, DigitsError> {
+    value.reverse_digits(radix)
+}
+
+// a macro writes the same impl for each type, generics would need a numeric trait crate
+macro_rules! impl_reverse_digits {
+    ($($t:ty),*) => {$(
+        impl ReverseDigits for $t {
+            fn reverse_digits(self, radix: u32) -> Result<Self, DigitsError> {
+                let base = <$t>::try_from(radix)
+                    .ok()
+                    .filter(|&base| base >= 2)
+                    .ok_or_else(|| DigitsError::InvalidRadix {
+                        radix,
+                        max: u32::try_from(<$t>::MAX).unwrap_or(u32::MAX),
+                        width: stringify!($t),
+                    })?;
+                let overflow = || DigitsError::Overflow {
+                    value: self.to_string(),
+                    radix,
+                    width: stringify!($t),
+                };
+
+                // for negative numbers % gives negative digits, so the result builds up negative too,
+                // this way MIN never has to be turned positive (which would overflow by itself)
+                let mut rest = self;
+                let mut reversed: $t = 0;
+                while rest != 0 {
+                    let digit = rest % base;
+                    reversed = reversed
+                        .checked_mul(base)
+                        .and_then(|r| r.checked_add(digit))
+                        .ok_or_else(overflow)?;
+                    rest /= base;
+                }
+                Ok(reversed)
+            }
+        }
+    )*};
+}
+
+impl_reverse_digits!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // the String round trip the walkthrough used to do, but in i128 so it can't overflow itself
+    fn reference(value: i64, radix: u32) -> i128 {
+        let mut digits = Vec::new();
+        let mut rest = (value as i128).abs();
+        while rest != 0 {
+            digits.push(rest % radix as i128);
+            rest /= radix as i128;
+        }
+        let reversed = digits.iter().fold(0, |acc, d| acc * radix as i128 + d);
+        reversed * (value as i128).signum()
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(reverse_digits(123, 10), Ok(321));
+        assert_eq!(reverse_digits(-123, 10), Ok(-321));
+        assert_eq!(reverse_digits(120u8, 10), Ok(21));
+        assert_eq!(reverse_digits(0i64, 10), Ok(0));
+        assert_eq!(reverse_digits(0b1011u16, 2), Ok(0b1101));
+        assert_eq!(reverse_digits(0xabci32, 16), Ok(0xcba));
+        assert_eq!(
+            reverse_digits(i32::MIN, 10),
+            Err(DigitsError::Overflow {
+                value: i32::MIN.to_string(),
+                radix: 10,
+                width: "i32",
+            })
+        );
+        assert!(reverse_digits(1_999_999_999i32, 10).is_err());
+        assert_eq!(reverse_digits(1_999_999_999i64, 10), Ok(9_999_999_991));
+    }
+
+    #[test]
+    fn invalid_radix() {
+        let invalid = |radix, max, width| DigitsError::InvalidRadix { radix, max, width };
+        assert_eq!(reverse_digits(5, 0), Err(invalid(0, i32::MAX as u32, "i32")));
+        assert_eq!(reverse_digits(5, 1), Err(invalid(1, i32::MAX as u32, "i32")));
+        assert_eq!(reverse_digits(5u8, 256), Err(invalid(256, 255, "u8")));
+        assert_eq!(reverse_digits(5i8, 128), Err(invalid(128, 127, "i8")));
+        assert_eq!(reverse_digits(5u8, 255), Ok(5));
+        assert_eq!(reverse_digits(5i16, 32_767), Ok(5));
+
+        // the message gives the range for the type, 2 or more alone is wrong for the small ones
+        let error = reverse_digits(5i8, 128).unwrap_err();
+        assert_eq!(error.to_string(), "radix 128 is not supported for i8, use 2 to 127");
+        let error = reverse_digits(5u64, 1).unwrap_err();
+        assert_eq!(error.to_string(), "radix 1 is not supported for u64, use 2 to 4294967295");
+    }
+
+    proptest! {
+        // same answer as the reference whenever it fits in an i32, an overflow error when it doesn't
+        #[test]
+        fn matches_reference_i32(value: i32, radix in 2u32..=36) {
+            let expected = reference(value as i64, radix);
+            match reverse_digits(value, radix) {
+                Ok(reversed) => prop_assert_eq!(reversed as i128, expected),
+                Err(e) => {
+                    prop_assert!(i32::try_from(expected).is_err());
+                    let is_overflow = matches!(e, DigitsError::Overflow { .. });
+                    prop_assert!(is_overflow);
+                }
+            }
+        }
+
+        #[test]
+        fn matches_reference_u8(value: u8, radix in 2u32..=255) {
+            let expected = reference(value as i64, radix);
+            prop_assert_eq!(reverse_digits(value, radix).ok(), u8::try_from(expected).ok());
+        }
+
+        #[test]
+        fn matches_reference_i64(value: i64, radix in 2u32..=64) {
+            let expected = reference(value, radix);
+            prop_assert_eq!(reverse_digits(value, radix).ok(), i64::try_from(expected).ok());
+        }
+
+        // without trailing zeros reversing twice gives the number back
+        #[test]
+        fn twice_is_identity(value: u64, radix in 2u32..=100) {
+            prop_assume!(value % radix as u64 != 0);
+            if let Ok(reversed) = reverse_digits(value, radix) {
+                prop_assert_eq!(reverse_digits(reversed, radix), Ok(value));
+            }
+        }
+
+        // the sign stays, zero stays zero
+        #[test]
+        fn keeps_sign(value: i128, radix in 2u32..=36) {
+            if let Ok(reversed) = reverse_digits(value, radix) {
+                prop_assert_eq!(reversed.signum(), value.signum());
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use plotters::drawing::DrawingAreaErrorKind;
+use polars::prelude::PolarsError;
+use thiserror::Error; //cargo add thiserror -> derives Display and Error for error enums
+
+use crate::digits::DigitsError;
+use crate::loader::LoadError;
+
+// One error type for the whole crate. Library errors are wrapped with #[from] so ? converts them
+// and source() still hands back the original, our own checks get a variant saying what kind of
+// mistake it was. Match on the variant, or use code() for a short name that won't change.
+// A message never repeats its source: either the variant is transparent (the library's own
+// message and source) or it says what we were doing and source() tells why, see main.rs.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Load(#[from] LoadError),
+    #[error(transparent)]
+    Digits(#[from] DigitsError),
+    #[error(transparent)]
+    Polars(#[from] PolarsError),
+    // a replay log or other JSON that couldn't be written or read back
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    // a linfa model refused the data or its parameters
+    #[error("{model} failed")]
+    Linfa {
+        model: &'static str,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    // plotters couldn't draw or save the chart
+    #[error("could not draw the chart")]
+    Plot(#[source] Box<dyn std::error::Error + Send + Sync>),
+    // arrays, DataFrames or reports whose sizes don't fit together
+    #[error("shape mismatch: {message}")]
+    Shape {
+        message: String,
+        source: Option<ndarray::ShapeError>,
+    },
+    // a column that is missing, has the wrong type or has unusable values
+    #[error("column \"{column}\" {problem}")]
+    Column { column: String, problem: String },
+    // an option or argument that can't work, e.g. a polynomial of degree 0
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    // stable short names, for scripts and JSON output, the messages may be reworded any time
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "io",
+            Error::Load(LoadError::NotFound(_)) => "load.not_found",
+            Error::Load(LoadError::Permission(_)) => "load.permission",
+            Error::Load(LoadError::Decode { .. }) => "load.decode",
+            Error::Load(LoadError::Io { .. }) => "load.io",
+            Error::Digits(DigitsError::InvalidRadix { .. }) => "digits.invalid_radix",
+            Error::Digits(DigitsError::Overflow { .. }) => "digits.overflow",
+            Error::Polars(_) => "polars",
+            Error::Json(_) => "json",
+            Error::Linfa { .. } => "linfa",
+            Error::Plot(_) => "plot",
+            Error::Shape { .. } => "shape",
+            Error::Column { .. } => "column",
+            Error::InvalidParameter(_) => "invalid_parameter",
+        }
+    }
+
+    pub fn shape(message: impl Into<String>) -> Self {
+        Error::Shape {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn column(column: impl Into<String>, problem: impl Into<String>) -> Self {
+        Error::Column {
+            column: column.into(),
+            problem: problem.into(),
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Error::InvalidParameter(message.into())
+    }
+
+    // for map_err on a linfa fit: .map_err(Error::linfa("KMeans"))
+    pub fn linfa<E>(model: &'static str) -> impl FnOnce(E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        move |source| Error::Linfa {
+            model,
+            source: Box::new(source),
+        }
+    }
+}
+
+impl From<ndarray::ShapeError> for Error {
+    fn from(source: ndarray::ShapeError) -> Self {
+        Error::Shape {
+            message: "ndarray could not fit the data into the array".to_string(),
+            source: Some(source),
+        }
+    }
+}
+
+// whatever the backend, so ? works inside Figure::draw
+impl<E> From<DrawingAreaErrorKind<E>> for Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn from(source: DrawingAreaErrorKind<E>) -> Self {
+        Error::Plot(Box::new(source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn codes_and_sources() {
+        let io = Error::from(std::io::Error::other("disk on fire"));
+        assert_eq!(io.code(), "io");
+        assert_eq!(io.to_string(), "disk on fire");
+
+        // the message says what failed, the source why, neither repeats the other
+        let plot = Error::Plot(Box::new(std::io::Error::other("disk on fire")));
+        assert_eq!(plot.to_string(), "could not draw the chart");
+        assert_eq!(plot.source().unwrap().to_string(), "disk on fire");
+
+        let shape =
+            Error::from(ndarray::Array2::<f64>::from_shape_vec((2, 2), vec![1.0]).unwrap_err());
+        assert_eq!(shape.code(), "shape");
+        let reason = shape.source().unwrap().to_string();
+        assert!(!shape.to_string().contains(&reason));
+
+        let missing = Error::from(LoadError::NotFound("nope.txt".into()));
+        assert_eq!(missing.code(), "load.not_found");
+        assert!(matches!(missing, Error::Load(LoadError::NotFound(_))));
+
+        assert_eq!(Error::invalid("k = 0").code(), "invalid_parameter");
+        assert_eq!(
+            Error::column("x", "is missing").to_string(),
+            "column \"x\" is missing"
+        );
+    }
+}
@@ -0,0 +1,439 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::ops::BitOr;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize}; //cargo add serde --features derive -> (de)serialize our own types
+
+use crate::error::Result;
+
+// The Event enum from the enums lesson grown into an input event system:
+// handlers register for a kind of event, emit() puts events in a queue with a timestamp and
+// dispatch() hands them to the handlers in the order they came in. A recording of the events
+// that came from outside can be saved as JSON and replayed later, e.g. to test a UI without a user.
+
+// which of shift, ctrl, alt and meta were held down, combine them with |: Modifiers::CTRL | Modifiers::SHIFT
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+        meta: false,
+    };
+    pub const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ..Modifiers::NONE
+    };
+    pub const CTRL: Modifiers = Modifiers {
+        ctrl: true,
+        ..Modifiers::NONE
+    };
+    pub const ALT: Modifiers = Modifiers {
+        alt: true,
+        ..Modifiers::NONE
+    };
+    pub const META: Modifiers = Modifiers {
+        meta: true,
+        ..Modifiers::NONE
+    };
+
+    pub fn is_empty(self) -> bool {
+        self == Modifiers::NONE
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, other: Modifiers) -> Modifiers {
+        Modifiers {
+            shift: self.shift || other.shift,
+            ctrl: self.ctrl || other.ctrl,
+            alt: self.alt || other.alt,
+            meta: self.meta || other.meta,
+        }
+    }
+}
+
+// written as Ctrl+Shift, nothing when no modifier is held
+impl fmt::Display for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.meta, "Meta"),
+        ];
+        let held: Vec<&str> = names
+            .iter()
+            .filter(|(down, _)| *down)
+            .map(|(_, name)| *name)
+            .collect();
+        write!(f, "{}", held.join("+"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    Load,                                      //simply named event
+    Press { key: char, modifiers: Modifiers }, //a key, with what was held down
+    Click { x: i32, y: i32 },                  //named data (like a mini-struct)
+    Scroll { dx: f32, dy: f32 },               //wheel or touchpad, in lines
+    Drag { x: i32, y: i32, dx: i32, dy: i32 }, //moved to x, y by dx, dy with the button down
+    Resize { width: u32, height: u32 },        //the new window size
+}
+
+impl Event {
+    // a key press without modifiers, the most common case
+    pub fn press(key: char) -> Self {
+        Event::Press {
+            key,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::Load => EventKind::Load,
+            Event::Press { .. } => EventKind::Press,
+            Event::Click { .. } => EventKind::Click,
+            Event::Scroll { .. } => EventKind::Scroll,
+            Event::Drag { .. } => EventKind::Drag,
+            Event::Resize { .. } => EventKind::Resize,
+        }
+    }
+}
+
+// short description for logs, key presses come out as the shortcut, e.g. Ctrl+Shift+s
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Load => write!(f, "load"),
+            Event::Press { key, modifiers } if modifiers.is_empty() => write!(f, "{key}"),
+            Event::Press { key, modifiers } => write!(f, "{modifiers}+{key}"),
+            Event::Click { x, y } => write!(f, "click at ({x}, {y})"),
+            Event::Scroll { dx, dy } => write!(f, "scroll by ({dx}, {dy})"),
+            Event::Drag { x, y, dx, dy } => write!(f, "drag by ({dx}, {dy}) to ({x}, {y})"),
+            Event::Resize { width, height } => write!(f, "resize to {width}x{height}"),
+        }
+    }
+}
+
+// the variants without their data, what handlers register for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    Load,
+    Press,
+    Click,
+    Scroll,
+    Drag,
+    Resize,
+}
+
+// an event and when it was emitted, counted from when the bus was created
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    pub at: Duration,
+    pub event: Event,
+}
+
+// boxed because every closure is its own type, FnMut so a handler can keep counts and the like
+pub type Handler = Box<dyn FnMut(&TimedEvent)>;
+
+pub struct EventBus {
+    handlers: HashMap<EventKind, Vec<Handler>>,
+    queue: VecDeque<(TimedEvent, bool)>, //first in, first out, true for events a handler emitted
+    started: Instant,
+    recording: Option<ReplayLog>, //Some while record() is on
+    emitted: Rc<RefCell<Vec<TimedEvent>>>, //from Emitters, moved into the queue before it is read
+}
+
+// A handle for emitting events from inside a handler. Handlers can't borrow the bus while it
+// dispatches, so they capture one of these instead (bus.emitter()) and their events are queued
+// behind the ones already waiting, dispatch() keeps going until both are empty.
+// Handlers' events are left out of recordings, replaying on the same handlers emits them again.
+// A handler that answers every event of its own kind with another one never lets dispatch() return.
+#[derive(Clone)]
+pub struct Emitter {
+    emitted: Rc<RefCell<Vec<TimedEvent>>>,
+    started: Instant,
+}
+
+impl Emitter {
+    pub fn emit(&self, event: Event) {
+        let at = self.started.elapsed();
+        self.emitted.borrow_mut().push(TimedEvent { at, event });
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        EventBus {
+            handlers: HashMap::new(),
+            queue: VecDeque::new(),
+            started: Instant::now(),
+            recording: None,
+            emitted: Rc::default(),
+        }
+    }
+
+    // a handle handlers can capture to emit events while the bus dispatches, see Emitter
+    pub fn emitter(&self) -> Emitter {
+        Emitter {
+            emitted: Rc::clone(&self.emitted),
+            started: self.started,
+        }
+    }
+
+    // handlers for the same kind run in the order they were registered
+    pub fn on(&mut self, kind: EventKind, handler: impl FnMut(&TimedEvent) + 'static) {
+        self.handlers
+            .entry(kind)
+            .or_default()
+            .push(Box::new(handler));
+    }
+
+    // queues the event stamped with the time since the bus was created
+    pub fn emit(&mut self, event: Event) {
+        let at = self.started.elapsed();
+        self.emit_at(at, event);
+    }
+
+    // queues the event with a given timestamp, for replays and tests that need fixed times
+    pub fn emit_at(&mut self, at: Duration, event: Event) {
+        self.take_emitted(false); //an Emitter's events were first
+        self.queue.push_back((TimedEvent { at, event }, false));
+    }
+
+    pub fn pending(&self) -> usize {
+        self.queue.len() + self.emitted.borrow().len()
+    }
+
+    // runs the handlers for every queued event, oldest first, and returns how many were dispatched,
+    // events the handlers emit on the way are included
+    pub fn dispatch(&mut self) -> usize {
+        let mut dispatched = 0;
+        self.take_emitted(false); //emitted before the dispatch, not by a handler
+        while let Some((timed, derived)) = self.queue.pop_front() {
+            if let Some(handlers) = self.handlers.get_mut(&timed.event.kind()) {
+                for handler in handlers {
+                    handler(&timed);
+                }
+            }
+            self.take_emitted(true);
+            if let Some(log) = &mut self.recording
+                && !derived
+            {
+                log.events.push(timed);
+            }
+            dispatched += 1;
+        }
+        dispatched
+    }
+
+    // moves what the Emitters collected into the queue
+    fn take_emitted(&mut self, derived: bool) {
+        let emitted = self.emitted.borrow_mut().drain(..).collect::<Vec<_>>();
+        self.queue.extend(emitted.into_iter().map(|timed| (timed, derived)));
+    }
+
+    // from now on every dispatched event is kept, except the ones handlers emitted,
+    // stop_recording() hands them over
+    pub fn record(&mut self) {
+        self.recording.get_or_insert_with(ReplayLog::default);
+    }
+
+    pub fn stop_recording(&mut self) -> ReplayLog {
+        self.recording.take().unwrap_or_default()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus::new()
+    }
+}
+
+// a recorded stream of events that can be saved, loaded and dispatched again
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayLog {
+    pub events: Vec<TimedEvent>,
+}
+
+impl ReplayLog {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    // queues the events with their recorded timestamps and dispatches them on the given bus,
+    // its handlers emit whatever they emitted the first time again
+    pub fn replay(&self, bus: &mut EventBus) -> usize {
+        for timed in &self.events {
+            bus.emit_at(timed.at, timed.event.clone());
+        }
+        bus.dispatch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a bus that writes a line per key press, click and resize into the returned Vec
+    fn listening_bus() -> (EventBus, Rc<RefCell<Vec<String>>>) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = EventBus::new();
+        let keys = Rc::clone(&seen);
+        bus.on(EventKind::Press, move |timed| {
+            keys.borrow_mut().push(timed.event.to_string());
+        });
+        let clicks = Rc::clone(&seen);
+        bus.on(EventKind::Click, move |timed| {
+            clicks.borrow_mut().push(format!("click {:?}", timed.at));
+        });
+        let resizes = Rc::clone(&seen);
+        bus.on(EventKind::Resize, move |_| {
+            resizes.borrow_mut().push("resize".into())
+        });
+        (bus, seen)
+    }
+
+    #[test]
+    fn dispatches_in_order_to_matching_handlers() {
+        let (mut bus, seen) = listening_bus();
+        bus.emit_at(Duration::from_millis(5), Event::press('a'));
+        bus.emit_at(
+            Duration::from_millis(7),
+            Event::Scroll { dx: 0.0, dy: -3.0 },
+        );
+        bus.emit_at(Duration::from_millis(9), Event::Click { x: 1, y: 2 });
+        bus.emit(Event::Press {
+            key: 's',
+            modifiers: Modifiers::CTRL | Modifiers::SHIFT,
+        });
+        assert_eq!(bus.pending(), 4);
+
+        assert_eq!(bus.dispatch(), 4); //the scroll has no handler but still counts
+        assert_eq!(bus.pending(), 0);
+        assert_eq!(*seen.borrow(), ["a", "click 9ms", "Ctrl+Shift+s"]);
+        assert_eq!(bus.dispatch(), 0);
+    }
+
+    #[test]
+    fn replay_round_trip() {
+        let (mut bus, _) = listening_bus();
+        bus.emit_at(Duration::from_millis(1), Event::Load);
+        bus.dispatch(); //before recording, not in the log
+        bus.record();
+        bus.emit_at(Duration::from_millis(2), Event::press('q'));
+        bus.emit_at(
+            Duration::from_millis(3),
+            Event::Drag {
+                x: 4,
+                y: 5,
+                dx: 1,
+                dy: -1,
+            },
+        );
+        bus.emit_at(
+            Duration::from_millis(4),
+            Event::Resize {
+                width: 800,
+                height: 600,
+            },
+        );
+        bus.dispatch();
+        let log = bus.stop_recording();
+        assert_eq!(log.events.len(), 3);
+
+        let loaded = ReplayLog::from_json(&log.to_json().unwrap()).unwrap();
+        assert_eq!(loaded, log);
+
+        let (mut replayed, seen) = listening_bus();
+        assert_eq!(loaded.replay(&mut replayed), 3);
+        assert_eq!(*seen.borrow(), ["q", "resize"]);
+
+        let broken = ReplayLog::from_json("{\"events\": [{\"at\": 1}]}").unwrap_err();
+        assert_eq!(broken.code(), "json");
+    }
+
+    #[test]
+    fn handlers_emit_through_an_emitter() {
+        let (mut bus, seen) = listening_bus();
+        // every click is answered with a key press, which runs in the same dispatch
+        let emitter = bus.emitter();
+        bus.on(EventKind::Click, move |_| emitter.emit(Event::press('x')));
+        bus.emit(Event::Click { x: 0, y: 0 });
+        bus.emit(Event::press('y'));
+
+        assert_eq!(bus.dispatch(), 3);
+        assert_eq!(bus.pending(), 0);
+        assert_eq!(seen.borrow()[1..], ["y", "x"]); //after what was already queued
+
+        // outside of a dispatch it's just another way to queue, in order with emit()
+        bus.emitter().emit(Event::press('1'));
+        bus.emit(Event::press('2'));
+        assert_eq!(bus.pending(), 2);
+        bus.dispatch();
+        assert_eq!(seen.borrow()[3..], ["1", "2"]);
+    }
+
+    #[test]
+    fn replay_leaves_derived_events_to_the_handlers() {
+        // every resize is answered with a load, like in the enums lesson
+        let answering_bus = || {
+            let (mut bus, seen) = listening_bus();
+            let emitter = bus.emitter();
+            bus.on(EventKind::Resize, move |_| emitter.emit(Event::Load));
+            let loads = Rc::clone(&seen);
+            bus.on(EventKind::Load, move |_| loads.borrow_mut().push("load".into()));
+            (bus, seen)
+        };
+
+        let (mut bus, seen) = answering_bus();
+        bus.record();
+        bus.emitter().emit(Event::press('a')); //from outside, recorded like emit()
+        bus.emit(Event::Resize {
+            width: 10,
+            height: 10,
+        });
+        assert_eq!(bus.dispatch(), 3);
+        assert_eq!(*seen.borrow(), ["a", "resize", "load"]);
+        let log = bus.stop_recording();
+        assert_eq!(log.events.len(), 2); //the load came from the handler
+
+        let (mut replayed, seen) = answering_bus();
+        assert_eq!(log.replay(&mut replayed), 3);
+        assert_eq!(*seen.borrow(), ["a", "resize", "load"]); //one load, not two
+    }
+
+    #[test]
+    fn event_descriptions() {
+        let shortcut = Event::Press {
+            key: 's',
+            modifiers: Modifiers::SHIFT | Modifiers::CTRL,
+        };
+        assert_eq!(shortcut.to_string(), "Ctrl+Shift+s");
+        assert_eq!(Event::press('a').to_string(), "a");
+        assert_eq!(Modifiers::NONE.to_string(), "");
+        let resize = Event::Resize {
+            width: 800,
+            height: 600,
+        };
+        assert_eq!(resize.to_string(), "resize to 800x600");
+    }
+}
@@ -1,74 +1,1046 @@
 use linfa::prelude::*;
 use linfa_linear::LinearRegression; //cargo add linfa-linear -> for linear fits
 use ndarray::{Array1, Array2};
-use polars::prelude::*;
+use plotters::coord::Shift;
 use plotters::prelude::*;
-use std::error::Error;
+use polars::prelude::*;
+use std::fmt;
+
+use crate::chart::{self, Axis, AxisOptions, ChartAxes, Figure, RenderOptions, Rendered};
+use crate::data;
+use crate::error::{Error, Result};
+use crate::preprocessing::{FittedPipeline, Pipeline};
+use crate::stats;
+
+// model families, everything except Linear works on a single feature x
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Model {
+    Linear,            // y = b0 + b1 x1 + b2 x2 + ...
+    Polynomial(usize), // y = b0 + b1 x + b2 x^2 + ... up to the given degree
+    Exponential,       // y = e^(b0 + b1 x), fitted as a line on ln y so y has to be positive
+    Logarithmic,       // y = b0 + b1 ln x, x has to be positive
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Model::Linear => write!(f, "Linear"),
+            Model::Polynomial(degree) => write!(f, "Polynomial (degree {degree})"),
+            Model::Exponential => write!(f, "Exponential"),
+            Model::Logarithmic => write!(f, "Logarithmic"),
+        }
+    }
+}
+
+impl Model {
+    // the regression terms for one row of features, the model is linear in these
+    fn terms(&self, row: &[f64]) -> Vec<f64> {
+        match *self {
+            Model::Linear => row.to_vec(),
+            Model::Polynomial(degree) => (1..=degree as i32)
+                .map(|power| row[0].powi(power))
+                .collect(),
+            Model::Exponential => vec![row[0]],
+            Model::Logarithmic => vec![row[0].ln()],
+        }
+    }
+
+    // names of the terms, used in the coefficient report
+    fn term_names(&self, features: &[&str]) -> Vec<String> {
+        match *self {
+            Model::Linear => features.iter().map(|name| name.to_string()).collect(),
+            Model::Polynomial(degree) => (1..=degree)
+                .map(|power| match power {
+                    1 => features[0].to_string(),
+                    _ => format!("{}^{power}", features[0]),
+                })
+                .collect(),
+            Model::Exponential => vec![features[0].to_string()],
+            Model::Logarithmic => vec![format!("ln({})", features[0])],
+        }
+    }
+}
+
+// what compare_models ranks by, both are lower-is-better
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Criterion {
+    Aic, // Akaike information criterion
+    Bic, // Bayesian information criterion, punishes extra terms harder
+}
+
+// lower and upper end of an interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+// intervals around the fitted value at one point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bands {
+    pub fitted: f64,
+    pub confidence: Interval, // where the mean response lies
+    pub prediction: Interval, // where a new observation lies, always wider
+}
+
+// what fit_and_plot fits and how the fit is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitPlotOptions {
+    pub model: Model, // e.g. the winner of compare_models
+    pub axes: ChartAxes,
+    pub band_level: Option<f64>, // e.g. 0.95 for 95% bands, None draws no bands
+    pub render: RenderOptions,
+    pub residuals: Option<RenderOptions>, // also draw the residual diagnostics, None skips them
+    pub preprocessing: Option<Pipeline>, // rescale or encode columns before fitting, see preprocessing.rs
+}
+
+impl Default for FitPlotOptions {
+    fn default() -> Self {
+        FitPlotOptions {
+            model: Model::Linear,
+            axes: ChartAxes::default(),
+            band_level: Some(0.95),
+            render: RenderOptions::new("fit.png"),
+            residuals: None,
+            preprocessing: None,
+        }
+    }
+}
+
+// numeric checks on the residuals, a good fit leaves residuals that look like independent normal noise
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Diagnostics {
+    // 0..4, ~2 means no autocorrelation between neighbouring residuals (in row order),
+    // None for a perfect fit, the residuals it divides by are only rounding errors then
+    pub durbin_watson: Option<f64>,
+    pub skewness: f64,      // 0 for a symmetric distribution
+    pub kurtosis: f64,      // 3 for a normal distribution
+    pub jarque_bera: f64,   // normality test statistic, large means not normal
+    pub jarque_bera_p: f64, // p-value of the above, small (e.g. < 0.05) means not normal
+}
+
+impl Diagnostics {
+    // y is only there for its scale, to tell a perfect fit apart from a very good one
+    fn from_residuals(residuals: &Array1<f64>, y: &Array1<f64>) -> Self {
+        let n = residuals.len() as f64;
+        let mean = residuals.mean().unwrap_or(0.0);
+        let moment = |power: i32| {
+            residuals
+                .iter()
+                .map(|r| (r - mean).powi(power))
+                .sum::<f64>()
+                / n
+        };
+        let (m2, m3, m4) = (moment(2), moment(3), moment(4));
+
+        let sum_squares = residuals.mapv(|r| r * r).sum();
+        let successive = residuals
+            .windows(2)
+            .into_iter()
+            .map(|pair| (pair[1] - pair[0]).powi(2))
+            .sum::<f64>();
+
+        let (skewness, kurtosis) = if m2 > 0.0 {
+            (m3 / m2.powf(1.5), m4 / (m2 * m2))
+        } else {
+            (0.0, 3.0) //perfect fit, nothing to be skewed
+        };
+        let jarque_bera = n / 6.0 * (skewness.powi(2) + (kurtosis - 3.0).powi(2) / 4.0);
+
+        Diagnostics {
+            durbin_watson: if sum_squares > f64::EPSILON * y.mapv(|v| v * v).sum() {
+                Some(successive / sum_squares)
+            } else {
+                None
+            },
+            skewness,
+            kurtosis,
+            jarque_bera,
+            jarque_bera_p: (-jarque_bera / 2.0).exp(), //chi-squared with 2 degrees of freedom
+        }
+    }
+}
+
+// everything we know about a fitted regression, keeps the data so it can be plotted later
+#[derive(Debug, Clone)]
+pub struct FitResult {
+    pub model: Model,
+    pub features: Vec<String>, // feature column names
+    pub target: String,        // target column name
+    pub terms: Vec<String>,    // names of the fitted terms, e.g. x, x^2, ln(x)
+    pub intercept: f64,
+    pub coefficients: Vec<f64>, // one per term, same order as `terms`
+    pub r_squared: f64,
+    pub adjusted_r_squared: f64, // NaN when there are not enough rows for the number of terms
+    pub rmse: f64,
+    pub aic: f64,
+    pub bic: f64,
+    pub dof: usize,              // residual degrees of freedom, n - terms - 1
+    pub residual_variance: f64,  // on the scale the model is linear in (ln y for Exponential)
+    pub covariance: Array2<f64>, // of [intercept, coefficients...], NaN if it couldn't be estimated
+    pub x: Array2<f64>,          // (n, features) rows that went into the fit
+    pub y: Array1<f64>,          // observed target
+    pub fitted: Array1<f64>,     // predicted target
+    pub residuals: Array1<f64>,  // observed - predicted
+    pub diagnostics: Diagnostics,
+    pub preprocessing: Option<FittedPipeline>, // set by fit_and_plot, transform new data with it before predicting
+}
+
+// coefficient report, e.g. println!("{result}")
+impl fmt::Display for FitResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let linear = format!("intercept + {}", self.terms.join(" + "));
+        match self.model {
+            Model::Exponential => {
+                writeln!(f, "{} fit: {} ~ exp({linear})", self.model, self.target)?
+            }
+            _ => writeln!(f, "{} fit: {} ~ {linear}", self.model, self.target)?,
+        }
+        writeln!(f, "  {:<12} {:>12.4}", "intercept", self.intercept)?;
+        for (name, coefficient) in self.terms.iter().zip(&self.coefficients) {
+            writeln!(f, "  {name:<12} {coefficient:>12.4}")?;
+        }
+        writeln!(
+            f,
+            "  R² = {:.4}, adjusted R² = {:.4}, RMSE = {:.4}, AIC = {:.2}, BIC = {:.2}, n = {}",
+            self.r_squared,
+            self.adjusted_r_squared,
+            self.rmse,
+            self.aic,
+            self.bic,
+            self.y.len()
+        )?;
+        match self.diagnostics.durbin_watson {
+            Some(dw) => write!(f, "  Durbin-Watson = {dw:.3}, ")?,
+            None => write!(f, "  Durbin-Watson = n/a (perfect fit), ")?,
+        }
+        write!(
+            f,
+            "Jarque-Bera = {:.3} (p = {:.3})",
+            self.diagnostics.jarque_bera, self.diagnostics.jarque_bera_p
+        )
+    }
+}
+
+impl FitResult {
+    // model prediction for one row of feature values, in the same order as `features`
+    pub fn predict(&self, row: &[f64]) -> f64 {
+        let linear = self.intercept
+            + self
+                .model
+                .terms(row)
+                .iter()
+                .zip(&self.coefficients)
+                .map(|(term, coefficient)| term * coefficient)
+                .sum::<f64>();
+        match self.model {
+            Model::Exponential => linear.exp(),
+            _ => linear,
+        }
+    }
+
+    // Confidence and prediction intervals at one row of feature values for the given level (e.g. 0.95).
+    // They come from the t distribution of the residuals, None when there are no degrees of freedom left.
+    // The level has to be strictly between 0 and 1.
+    pub fn bands(&self, row: &[f64], level: f64) -> Result<Option<Bands>> {
+        check_level(level)?;
+        if self.dof == 0 || self.covariance.iter().any(|v| v.is_nan()) {
+            return Ok(None);
+        }
+
+        // variance of the fitted mean is t' Cov t for t = [1, terms...]
+        let mut terms = vec![1.0];
+        terms.extend(self.model.terms(row));
+        let mean_variance: f64 = (0..terms.len())
+            .flat_map(|i| (0..terms.len()).map(move |j| (i, j)))
+            .map(|(i, j)| terms[i] * self.covariance[(i, j)] * terms[j])
+            .sum();
+
+        let t = stats::student_t_quantile(0.5 + level / 2.0, self.dof as f64);
+        let centre = self.intercept
+            + terms[1..]
+                .iter()
+                .zip(&self.coefficients)
+                .map(|(term, coefficient)| term * coefficient)
+                .sum::<f64>();
+        let mean_half = t * mean_variance.max(0.0).sqrt();
+        let prediction_half = t * (mean_variance + self.residual_variance).max(0.0).sqrt();
+
+        // intervals are symmetric on the linear scale, the exponential model maps them back with exp
+        let back = |v: f64| match self.model {
+            Model::Exponential => v.exp(),
+            _ => v,
+        };
+        let interval = |half: f64| Interval {
+            lower: back(centre - half),
+            upper: back(centre + half),
+        };
+
+        Ok(Some(Bands {
+            fitted: back(centre),
+            confidence: interval(mean_half),
+            prediction: interval(prediction_half),
+        }))
+    }
+}
+
+// a band level of 0 or 1 (or beyond) has no interval, better to say so than to draw nothing
+fn check_level(level: f64) -> Result<()> {
+    if level > 0.0 && level < 1.0 {
+        Ok(())
+    } else {
+        Err(Error::invalid(format!(
+            "band level must be between 0 and 1 (e.g. 0.95), got {level}"
+        )))
+    }
+}
+
+// fits target against one or more feature columns with a linear model, nothing is drawn
+pub fn fit(df: &DataFrame, features: &[&str], target: &str) -> Result<FitResult> {
+    fit_model(df, features, target, Model::Linear)
+}
+
+// fits any of the model families, nothing is drawn
+pub fn fit_model(
+    df: &DataFrame,
+    features: &[&str],
+    target: &str,
+    model: Model,
+) -> Result<FitResult> {
+    match model {
+        Model::Linear => {}
+        Model::Polynomial(0) => {
+            return Err(Error::invalid("polynomial degree has to be at least 1"));
+        }
+        _ if features.len() != 1 => {
+            return Err(Error::invalid(format!(
+                "{model} fits take exactly one feature column, got {}",
+                features.len()
+            )));
+        }
+        _ => {}
+    }
 
-pub fn fit_and_plot(df: &DataFrame) -> Result<(), Box<dyn Error>> {
     // Extract columns
-    let x = df.column("x")?.f64()?;
-    let y = df.column("y")?.f64()?;
+    let x = data::f64_columns(df, features)?;
+    let y = data::f64_column(df, target)?;
 
-    // Prepare ndarray input
-    let features: Vec<f64> = x
-        .into_iter()
-        .filter_map(|opt| opt)
-        .collect();
-    let targets: Vec<f64> = y
-        .into_iter()
-        .filter_map(|opt| opt)
-        .collect();
+    // Prepare ndarray input, rows with a null in any of the used columns are skipped
+    let mut flat: Vec<f64> = Vec::new();
+    let mut targets: Vec<f64> = Vec::new();
+    for row in 0..df.height() {
+        let values: Option<Vec<f64>> = x.iter().map(|column| column.get(row)).collect();
+        if let (Some(values), Some(target)) = (values, y.get(row)) {
+            flat.extend(values);
+            targets.push(target);
+        }
+    }
 
-    let n = features.len();
-    let x_array = Array2::from_shape_vec((n, 1), features)?;
+    let n = targets.len();
+    let x_array = Array2::from_shape_vec((n, features.len()), flat)?;
     let y_array = Array1::from_vec(targets);
 
-    // Train model
-    let dataset = DatasetBase::new(x_array.view(), y_array.view());
-    let model = LinearRegression::default().fit(&dataset)?;
-
-    // Predict
-    let y_pred = model.predict(&dataset);
-
-    // Plot results
-
-    // base colours
-    let base = RGBColor(30, 30, 46);
-    let text = RGBColor(205, 214, 244);
-    let grid = RGBColor(108, 112, 134);
-
-    let root = BitMapBackend::new("fit.png", (800, 600)).into_drawing_area();
-    root.fill(&base)?;
-
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Linear Fit", ("sans-serif", 30).into_font().color(&text))
-        .margin(20)
-        .x_label_area_size(30)
-        .y_label_area_size(30)
-        .build_cartesian_2d(0.0..10.0, 0.0..10.0)?;
-
-    chart.configure_mesh()
-    .axis_style(&text)
-    .light_line_style(&grid)
-    .label_style(("sans-serif", 20).into_font().color(&text))
-    .draw()?;
-
-    // Draw original points
-    for (x, y) in x_array.column(0).iter().zip(y_array.iter()) {
-        chart.draw_series(std::iter::once(Circle::new((*x, *y), 4, RGBColor(137, 220, 235).filled())))?;
-    }
-
-    // Draw predicted line
-    let line: Vec<(f64, f64)> = x_array
-        .column(0)
-        .iter()
-        .zip(y_pred.iter())
-        .map(|(&x, &y)| (x, y))
+    if model == Model::Logarithmic && x_array.iter().any(|&x| x <= 0.0) {
+        return Err(Error::column(features[0], "needs values > 0 for a logarithmic fit"));
+    }
+    if model == Model::Exponential && y_array.iter().any(|&y| y <= 0.0) {
+        return Err(Error::column(target, "needs values > 0 for an exponential fit"));
+    }
+
+    // every model is a linear regression on transformed terms (and ln y for the exponential one)
+    let term_rows: Vec<f64> = x_array
+        .rows()
+        .into_iter()
+        .flat_map(|row| model.terms(&row.to_vec()))
         .collect();
+    let terms = model.term_names(features);
+    let p = terms.len();
+    let design = Array2::from_shape_vec((n, p), term_rows)?;
+    let linear_target = match model {
+        Model::Exponential => y_array.mapv(f64::ln),
+        _ => y_array.clone(),
+    };
+
+    // Train model
+    let dataset = DatasetBase::new(design.view(), linear_target.view());
+    let regression = LinearRegression::default()
+        .fit(&dataset)
+        .map_err(Error::linfa("linear regression"))?;
+
+    // parameter covariance s² (X'X)^-1 on the linear scale, X has a leading column of ones for the intercept
+    let linear_residuals = &linear_target - &regression.predict(&dataset);
+    let dof = n.saturating_sub(p + 1);
+    let residual_variance = if dof > 0 {
+        linear_residuals.mapv(|r| r * r).sum() / dof as f64
+    } else {
+        f64::NAN
+    };
+    let mut with_intercept = Array2::<f64>::ones((n, p + 1));
+    with_intercept
+        .slice_mut(ndarray::s![.., 1..])
+        .assign(&design);
+    let covariance = match stats::invert(&with_intercept.t().dot(&with_intercept)) {
+        Some(inverse) => inverse * residual_variance,
+        None => Array2::from_elem((p + 1, p + 1), f64::NAN), //collinear terms, no bands for this one
+    };
+
+    // Predict, back on the original scale of y
+    let y_pred = match model {
+        Model::Exponential => regression.predict(&dataset).mapv(f64::exp),
+        _ => regression.predict(&dataset),
+    };
+
+    // Statistics
+    let residuals = &y_array - &y_pred;
+    let ss_res = residuals.mapv(|r| r * r).sum();
+    let mean = y_array.mean().unwrap_or(0.0);
+    let ss_tot = y_array.mapv(|y| (y - mean).powi(2)).sum();
+    let r_squared = if ss_tot > 0.0 {
+        1.0 - ss_res / ss_tot
+    } else {
+        1.0
+    }; //constant target is fitted perfectly
+    let adjusted_r_squared = if dof > 0 {
+        1.0 - (1.0 - r_squared) * (n as f64 - 1.0) / dof as f64
+    } else {
+        f64::NAN
+    };
+
+    // information criteria for least squares with normal errors, k counts the intercept too
+    let k = (p + 1) as f64;
+    let log_likelihood_term = n as f64 * (ss_res / n as f64).max(f64::MIN_POSITIVE).ln();
+    let aic = log_likelihood_term + 2.0 * k;
+    let bic = log_likelihood_term + k * (n as f64).ln();
+
+    let diagnostics = Diagnostics::from_residuals(&residuals, &y_array);
 
-    chart.draw_series(LineSeries::new(line, RED.stroke_width(3)))?;
+    Ok(FitResult {
+        model,
+        features: features.iter().map(|name| name.to_string()).collect(),
+        target: target.to_string(),
+        terms,
+        intercept: regression.intercept(),
+        coefficients: regression.params().to_vec(),
+        r_squared,
+        adjusted_r_squared,
+        rmse: (ss_res / n as f64).sqrt(),
+        aic,
+        bic,
+        dof,
+        residual_variance,
+        covariance,
+        x: x_array,
+        y: y_array,
+        fitted: y_pred,
+        diagnostics,
+        preprocessing: None,
+        residuals,
+    })
+}
 
-    println!("✅ Saved fit plot to fit.png");
-    Ok(())
-}
\ No newline at end of file
+// what compare_models found: the models that fit, best first, and the ones that couldn't
+#[derive(Debug)]
+pub struct Comparison {
+    pub ranking: Vec<FitResult>,
+    pub skipped: Vec<(Model, Error)>, // e.g. exponential with negative y, the others can still be compared
+}
+
+// fits every candidate model on one feature and ranks them,
+// an error only if none of them fit
+pub fn compare_models(
+    df: &DataFrame,
+    feature: &str,
+    target: &str,
+    models: &[Model],
+    criterion: Criterion,
+) -> Result<Comparison> {
+    let mut results = Vec::new();
+    let mut skipped = Vec::new();
+    for &model in models {
+        match fit_model(df, &[feature], target, model) {
+            Ok(result) => results.push(result),
+            Err(e) => skipped.push((model, e)),
+        }
+    }
+    if results.is_empty() {
+        return Err(Error::invalid(format!(
+            "none of the models could fit {target} ~ {feature}"
+        )));
+    }
+
+    let score = |result: &FitResult| match criterion {
+        Criterion::Aic => result.aic,
+        Criterion::Bic => result.bic,
+    };
+    results.sort_by(|a, b| score(a).total_cmp(&score(b)));
+    Ok(Comparison {
+        ranking: results,
+        skipped,
+    })
+}
+
+// preprocesses, fits the options' model and then plots it against the first feature,
+// returns the fit with the chart and the residual diagnostics (when the options ask for them)
+pub fn fit_and_plot(
+    df: &DataFrame,
+    features: &[&str],
+    target: &str,
+    options: &FitPlotOptions,
+) -> Result<(FitResult, Rendered, Option<Rendered>)> {
+    let result = match &options.preprocessing {
+        Some(pipeline) => {
+            let (fitted, df) = pipeline.fit_transform(df)?;
+            let mut result = fit_model(&df, features, target, options.model)?;
+            result.preprocessing = Some(fitted);
+            result
+        }
+        None => fit_model(df, features, target, options.model)?,
+    };
+    let rendered = plot_fit(&result, options)?;
+    let residuals = match &options.residuals {
+        Some(render) => Some(plot_residuals(&result, render)?),
+        None => None,
+    };
+    Ok((result, rendered, residuals))
+}
+
+// draws the observed points, the fitted line and its bands against the first feature
+pub fn plot_fit(result: &FitResult, options: &FitPlotOptions) -> Result<Rendered> {
+    if let Some(level) = options.band_level {
+        check_level(level)?;
+    }
+    chart::render(&FitFigure { result, options }, &options.render)
+}
+
+// residual diagnostics: residuals vs fitted, normal Q-Q plot and a histogram side by side
+pub fn plot_residuals(
+    result: &FitResult,
+    render: &RenderOptions,
+) -> Result<Rendered> {
+    if result.residuals.len() < 2 {
+        return Err(Error::shape(format!(
+            "need at least two residuals to plot, got {}",
+            result.residuals.len()
+        )));
+    }
+    chart::render(&ResidualFigure { result }, render)
+}
+
+struct FitFigure<'a> {
+    result: &'a FitResult,
+    options: &'a FitPlotOptions,
+}
+
+impl Figure for FitFigure<'_> {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        render: &RenderOptions,
+    ) -> Result<()>
+    where
+        DB::ErrorType: 'static,
+    {
+        let (result, options) = (self.result, self.options);
+        let axes = &options.axes;
+        let px = |size: u32| render.px(size); //sizes below are for scale 1.0
+        let x_array = &result.x;
+        let y_array = &result.y;
+        let y_pred = &result.fitted;
+
+        // base colours from the theme, see theme.rs
+        let palette = &render.theme.palette;
+        let base = palette.base;
+        let text = palette.text;
+        let grid = palette.overlay0;
+
+        // axis extents from the data and the fitted line, see chart.rs
+        let x_axis = Axis::fit(x_array.column(0).iter().copied(), &axes.x)?;
+
+        // with a single feature the model is a curve in x, sample it across the chart so it's smooth
+        let curve: Vec<(f64, f64)> = if result.features.len() == 1 {
+            let (start, end) = (x_axis.range.start, x_axis.range.end);
+            (0..=200)
+                .map(|i| x_axis.to_data(start + (end - start) * i as f64 / 200.0))
+                .filter(|&x| result.model != Model::Logarithmic || x > 0.0)
+                .map(|x| (x, result.predict(&[x])))
+                .filter(|(_, y)| y.is_finite() && (!axes.y.log_scale || *y > 0.0))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // bands along the same curve, (x, bands) pairs
+        let mut bands: Vec<(f64, Bands)> = Vec::new();
+        if let Some(level) = options.band_level {
+            for &(x, _) in &curve {
+                match result.bands(&[x], level)? {
+                    Some(b) if !axes.y.log_scale || b.prediction.lower > 0.0 => bands.push((x, b)),
+                    _ => {}
+                }
+            }
+        }
+
+        let y_values = y_array.iter().chain(y_pred.iter()).copied();
+        let curve_values = curve.iter().map(|&(_, y)| y);
+        let band_values = bands
+            .iter()
+            .flat_map(|(_, b)| [b.prediction.lower, b.prediction.upper]);
+        let y_axis = Axis::fit(y_values.chain(curve_values).chain(band_values), &axes.y)?;
+
+        root.fill(&base)?;
+
+        let mut chart = ChartBuilder::on(root)
+            .caption(
+                format!("{} Fit", result.model),
+                ("sans-serif", px(30)).into_font().color(&text),
+            )
+            .margin(px(20))
+            .x_label_area_size(px(40))
+            .y_label_area_size(px(40))
+            .build_cartesian_2d(x_axis.range.clone(), y_axis.range.clone())?;
+
+        chart
+            .configure_mesh()
+            .axis_style(text)
+            .light_line_style(grid)
+            .label_style(("sans-serif", px(20)).into_font().color(&text))
+            .x_label_formatter(&|v| x_axis.label(*v))
+            .y_label_formatter(&|v| y_axis.label(*v))
+            .x_desc(result.features[0].as_str())
+            .y_desc(result.target.as_str())
+            .draw()?;
+
+        // Shade the bands first so the points and the curve stay on top
+        if !bands.is_empty() {
+            let band = |pick: fn(&Bands) -> Interval| -> Vec<(f64, f64)> {
+                let upper = bands.iter().map(|(x, b)| (*x, pick(b).upper));
+                let lower = bands.iter().rev().map(|(x, b)| (*x, pick(b).lower));
+                upper
+                    .chain(lower)
+                    .map(|(x, y)| (x_axis.to_chart(x), y_axis.to_chart(y)))
+                    .collect()
+            };
+            let prediction_colour = palette.blue;
+            let confidence_colour = palette.mauve;
+            let prediction_band =
+                Polygon::new(band(|b| b.prediction), prediction_colour.mix(0.15).filled());
+            let confidence_band =
+                Polygon::new(band(|b| b.confidence), confidence_colour.mix(0.35).filled());
+            chart.draw_series(std::iter::once(prediction_band))?;
+            chart.draw_series(std::iter::once(confidence_band))?;
+        }
+
+        // Draw original points
+        for (x, y) in x_array.column(0).iter().zip(y_array.iter()) {
+            let point = (x_axis.to_chart(*x), y_axis.to_chart(*y));
+            chart.draw_series(std::iter::once(Circle::new(
+                point,
+                px(4),
+                palette.sky.filled(),
+            )))?;
+        }
+
+        // Draw predicted curve
+        if result.features.len() == 1 {
+            let line = curve
+                .into_iter()
+                .map(|(x, y)| (x_axis.to_chart(x), y_axis.to_chart(y)));
+            chart.draw_series(LineSeries::new(line, palette.red.stroke_width(px(3))))?;
+        } else {
+            // with more features the prediction isn't a line in this view, so just mark each predicted point
+            let first_feature = x_array.column(0);
+            let points = first_feature
+                .iter()
+                .zip(y_pred.iter())
+                .map(|(&x, &y)| (x_axis.to_chart(x), y_axis.to_chart(y)));
+            chart.draw_series(
+                points.map(|point| Cross::new(point, px(5), palette.red.stroke_width(px(2)))),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+struct ResidualFigure<'a> {
+    result: &'a FitResult,
+}
+
+impl Figure for ResidualFigure<'_> {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        render: &RenderOptions,
+    ) -> Result<()>
+    where
+        DB::ErrorType: 'static,
+    {
+        let result = self.result;
+        let residuals = &result.residuals;
+        let n = residuals.len();
+        let px = |size: u32| render.px(size); //sizes below are for scale 1.0
+
+        // base colours from the theme, see theme.rs
+        let palette = &render.theme.palette;
+        let base = palette.base;
+        let text = palette.text;
+        let grid = palette.overlay0;
+        let points = palette.sky;
+        let bars = palette.mauve;
+
+        root.fill(&base)?;
+        let panels = root.split_evenly((1, 3));
+
+        let padded = AxisOptions::default();
+
+        // residuals vs fitted, should be a shapeless cloud around zero
+        {
+            let x_axis = Axis::fit(result.fitted.iter().copied(), &padded)?;
+            let y_axis = Axis::fit(residuals.iter().copied().chain([0.0]), &padded)?;
+            let mut chart = ChartBuilder::on(&panels[0])
+                .caption(
+                    "Residuals vs Fitted",
+                    ("sans-serif", px(26)).into_font().color(&text),
+                )
+                .margin(px(15))
+                .x_label_area_size(px(40))
+                .y_label_area_size(px(50))
+                .build_cartesian_2d(x_axis.range.clone(), y_axis.range.clone())?;
+            chart
+                .configure_mesh()
+                .axis_style(text)
+                .light_line_style(grid)
+                .label_style(("sans-serif", px(16)).into_font().color(&text))
+                .x_label_formatter(&|v| x_axis.label(*v))
+                .y_label_formatter(&|v| y_axis.label(*v))
+                .x_desc("fitted")
+                .y_desc("residual")
+                .draw()?;
+            chart.draw_series(LineSeries::new(
+                [(x_axis.range.start, 0.0), (x_axis.range.end, 0.0)],
+                palette.red.stroke_width(px(2)),
+            ))?;
+            chart.draw_series(
+                result
+                    .fitted
+                    .iter()
+                    .zip(residuals.iter())
+                    .map(|(&x, &y)| Circle::new((x, y), px(4), points.filled())),
+            )?;
+        }
+
+        // normal Q-Q, standardised residuals against normal quantiles, should follow the diagonal
+        {
+            let mean = residuals.mean().unwrap_or(0.0);
+            let sd = residuals.std(1.0);
+            let mut sorted: Vec<f64> = residuals
+                .iter()
+                .map(|r| if sd > 0.0 { (r - mean) / sd } else { 0.0 })
+                .collect();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            let qq: Vec<(f64, f64)> = sorted
+                .iter()
+                .enumerate()
+                .map(|(i, &r)| {
+                    (
+                        stats::normal_quantile((i as f64 + 0.625) / (n as f64 + 0.25)),
+                        r,
+                    )
+                }) //Blom's plotting positions
+                .collect();
+
+            let x_axis = Axis::fit(qq.iter().map(|p| p.0), &padded)?;
+            let y_axis = Axis::fit(qq.iter().map(|p| p.1), &padded)?;
+            let mut chart = ChartBuilder::on(&panels[1])
+                .caption(
+                    "Normal Q-Q",
+                    ("sans-serif", px(26)).into_font().color(&text),
+                )
+                .margin(px(15))
+                .x_label_area_size(px(40))
+                .y_label_area_size(px(50))
+                .build_cartesian_2d(x_axis.range.clone(), y_axis.range.clone())?;
+            chart
+                .configure_mesh()
+                .axis_style(text)
+                .light_line_style(grid)
+                .label_style(("sans-serif", px(16)).into_font().color(&text))
+                .x_label_formatter(&|v| x_axis.label(*v))
+                .y_label_formatter(&|v| y_axis.label(*v))
+                .x_desc("theoretical quantile")
+                .y_desc("standardised residual")
+                .draw()?;
+            let (lo, hi) = (x_axis.range.start, x_axis.range.end);
+            chart.draw_series(LineSeries::new(
+                [(lo, lo), (hi, hi)],
+                palette.red.stroke_width(px(2)),
+            ))?;
+            chart.draw_series(
+                qq.into_iter()
+                    .map(|p| Circle::new(p, px(4), points.filled())),
+            )?;
+        }
+
+        // histogram, roughly bell shaped for normal residuals
+        {
+            let bins = (n as f64).sqrt().ceil().max(3.0) as usize;
+            let x_axis = Axis::fit(residuals.iter().copied(), &padded)?;
+            let (lo, hi) = (x_axis.range.start, x_axis.range.end);
+            let width = (hi - lo) / bins as f64;
+            let mut counts = vec![0usize; bins];
+            for &r in residuals {
+                let bin = (((r - lo) / width) as usize).min(bins - 1);
+                counts[bin] += 1;
+            }
+            let max_count = counts.iter().copied().max().unwrap_or(1);
+
+            let mut chart = ChartBuilder::on(&panels[2])
+                .caption(
+                    "Residual Histogram",
+                    ("sans-serif", px(26)).into_font().color(&text),
+                )
+                .margin(px(15))
+                .x_label_area_size(px(40))
+                .y_label_area_size(px(50))
+                .build_cartesian_2d(lo..hi, 0.0..(max_count as f64 * 1.1))?;
+            chart
+                .configure_mesh()
+                .axis_style(text)
+                .light_line_style(grid)
+                .label_style(("sans-serif", px(16)).into_font().color(&text))
+                .x_label_formatter(&|v| x_axis.label(*v))
+                .x_desc("residual")
+                .y_desc("count")
+                .draw()?;
+            chart.draw_series(counts.iter().enumerate().map(|(i, &count)| {
+                let left = lo + i as f64 * width;
+                let gap = width * 0.05; //keeps neighbouring bars apart
+                Rectangle::new(
+                    [(left + gap, 0.0), (left + width - gap, count as f64)],
+                    bars.mix(0.8).filled(),
+                )
+            }))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    // y worked out from x with the given function, no noise
+    fn exact(f: impl Fn(f64) -> f64) -> DataFrame {
+        let x: Vec<f64> = (1..=10).map(f64::from).collect();
+        let y: Vec<f64> = x.iter().map(|&x| f(x)).collect();
+        df!["x" => x, "y" => y].unwrap()
+    }
+
+    #[test]
+    fn residual_diagnostics() {
+        // alternating residuals: every step is 2 wide, so DW = 4 * 3 / 4, and they're symmetric
+        // with no tails, skewness 0 and kurtosis 1, JB = 4 / 6 * (1 - 3)² / 4
+        let y = Array1::from_vec(vec![10.0; 4]);
+        let d = Diagnostics::from_residuals(&Array1::from_vec(vec![1.0, -1.0, 1.0, -1.0]), &y);
+        assert!(close(d.durbin_watson.unwrap(), 3.0));
+        assert!(close(d.skewness, 0.0) && close(d.kurtosis, 1.0));
+        assert!(close(d.jarque_bera, 2.0 / 3.0));
+        assert!(close(d.jarque_bera_p, (-1.0f64 / 3.0).exp()));
+
+        // one outlier: mean 3/4, skewness 2/√3, kurtosis 7/3, JB = 4 / 6 * (4/3 + 1/9) = 26/27
+        let d = Diagnostics::from_residuals(&Array1::from_vec(vec![0.0, 0.0, 0.0, 3.0]), &y);
+        assert!(close(d.durbin_watson.unwrap(), 1.0)); //one jump of 3, over 3²
+        assert!(close(d.skewness, 2.0 / 3f64.sqrt()) && close(d.kurtosis, 7.0 / 3.0));
+        assert!(close(d.jarque_bera, 26.0 / 27.0));
+        assert!(close(d.jarque_bera_p, (-13.0f64 / 27.0).exp())); //≈ 0.618
+
+        // a perfect fit leaves rounding errors, Durbin-Watson would be noise over noise
+        let result = fit(&exact(|x| 2.0 * x + 1.0), &["x"], "y").unwrap();
+        assert_eq!(result.diagnostics.durbin_watson, None);
+        assert!(result.to_string().contains("Durbin-Watson = n/a (perfect fit)"));
+        let noisy = df!["x" => [1.0, 2.0, 3.0, 4.0], "y" => [1.0, 3.0, 2.0, 4.0]].unwrap();
+        assert!(fit(&noisy, &["x"], "y").unwrap().diagnostics.durbin_watson.is_some());
+    }
+
+    #[test]
+    fn charts_come_back_from_fit_and_plot() {
+        let in_memory = |width, height| RenderOptions {
+            width,
+            height,
+            backend: chart::Backend::Rgb,
+            ..RenderOptions::new("")
+        };
+        let options = FitPlotOptions {
+            render: in_memory(200, 100),
+            residuals: Some(in_memory(300, 100)),
+            ..FitPlotOptions::default()
+        };
+        let noisy = df![
+            "x" => [1.0, 2.0, 3.0, 4.0, 5.0],
+            "y" => [1.1, 1.9, 3.2, 3.9, 5.1]
+        ]
+        .unwrap();
+        let (result, fit, residuals) = fit_and_plot(&noisy, &["x"], "y", &options).unwrap();
+        assert_eq!(result.y.len(), 5);
+        let Rendered::Rgb { width, height, pixels } = fit else {
+            panic!("the Rgb backend should give a buffer");
+        };
+        assert_eq!((width, height, pixels.len()), (200, 100, 200 * 100 * 3));
+        assert!(matches!(residuals, Some(Rendered::Rgb { width: 300, .. })));
+
+        let options = FitPlotOptions {
+            residuals: None,
+            ..options
+        };
+        assert!(fit_and_plot(&noisy, &["x"], "y", &options).unwrap().2.is_none());
+    }
+
+    #[test]
+    fn band_half_widths() {
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let y = [1.2, 1.9, 3.3, 3.8, 5.2, 5.9];
+        let result = fit(&df!["x" => x, "y" => y].unwrap(), &["x"], "y").unwrap();
+
+        // by hand: s² from the residuals with n - 2 degrees of freedom, Sxx around the mean of x
+        let n = x.len() as f64;
+        let mean = x.iter().sum::<f64>() / n;
+        let sxx: f64 = x.iter().map(|x| (x - mean).powi(2)).sum();
+        let s = (result.residuals.mapv(|r| r * r).sum() / (n - 2.0)).sqrt();
+        let t = stats::student_t_quantile(0.975, n - 2.0);
+        for at in [1.0, 3.5, 8.0] {
+            let bands = result.bands(&[at], 0.95).unwrap().unwrap();
+            let spread = 1.0 / n + (at - mean).powi(2) / sxx;
+            let confidence = t * s * spread.sqrt();
+            let prediction = t * s * (1.0 + spread).sqrt();
+            assert!(close(bands.fitted, result.predict(&[at])));
+            assert!(close(bands.confidence.upper - bands.fitted, confidence));
+            assert!(close(bands.fitted - bands.confidence.lower, confidence));
+            assert!(close(bands.prediction.upper - bands.fitted, prediction));
+        }
+
+        for level in [0.0, 1.0, 1.5, -0.5, f64::NAN] {
+            let error = result.bands(&[1.0], level).unwrap_err();
+            assert_eq!(error.code(), "invalid_parameter");
+        }
+        let options = FitPlotOptions {
+            band_level: Some(1.5),
+            ..FitPlotOptions::default()
+        };
+        assert!(plot_fit(&result, &options).is_err()); //before anything is drawn
+
+        // two points leave no degrees of freedom for the spread
+        let two = fit(&df!["x" => [1.0, 2.0], "y" => [1.0, 3.0]].unwrap(), &["x"], "y").unwrap();
+        assert!(two.bands(&[1.5], 0.95).unwrap().is_none());
+    }
+
+    #[test]
+    fn several_features() {
+        // y = 1 + 2·x1 - 3·x2 exactly, x2 isn't a multiple of x1
+        let x1 = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let x2 = [0.5, 0.1, 0.9, 0.3, 0.7, 0.2];
+        let y: Vec<f64> = x1.iter().zip(&x2).map(|(a, b)| 1.0 + 2.0 * a - 3.0 * b).collect();
+        let df = df!["x1" => x1, "x2" => x2, "y" => &y].unwrap();
+        let result = fit(&df, &["x1", "x2"], "y").unwrap();
+        assert_eq!(result.terms, ["x1", "x2"]);
+        assert!(close(result.intercept, 1.0));
+        assert!(close(result.coefficients[0], 2.0) && close(result.coefficients[1], -3.0));
+        assert!(close(result.r_squared, 1.0) && close(result.adjusted_r_squared, 1.0));
+        assert_eq!(result.dof, 3);
+        assert!(close(result.predict(&[10.0, 1.0]), 18.0));
+
+        // with noise: adjusted R² = 1 - (1 - R²)(n - 1) / (n - p - 1), n = 6 rows, p = 2 features
+        let noise = [0.1, -0.2, 0.05, 0.15, -0.1, 0.0];
+        let noisy: Vec<f64> = y.iter().zip(noise).map(|(y, e)| y + e).collect();
+        let df = df!["x1" => x1, "x2" => x2, "y" => noisy].unwrap();
+        let result = fit(&df, &["x1", "x2"], "y").unwrap();
+        assert!(result.r_squared < 1.0);
+        assert!(close(result.adjusted_r_squared, 1.0 - (1.0 - result.r_squared) * 5.0 / 3.0));
+
+        // three rows for an intercept and two slopes leave nothing to adjust with
+        let df = df![
+            "x1" => [1.0, 2.0, 3.0],
+            "x2" => [0.5, 0.1, 0.9],
+            "y" => [1.0, 4.0, 2.0]
+        ]
+        .unwrap();
+        let result = fit(&df, &["x1", "x2"], "y").unwrap();
+        assert_eq!(result.dof, 0);
+        assert!(result.adjusted_r_squared.is_nan());
+        assert!(result.to_string().contains("adjusted R² = NaN"));
+    }
+
+    #[test]
+    fn model_families() {
+        let parabola = exact(|x| 1.0 + 2.0 * x + 3.0 * x * x);
+        let result = fit_model(&parabola, &["x"], "y", Model::Polynomial(2)).unwrap();
+        assert_eq!(result.terms, ["x", "x^2"]);
+        assert!(close(result.intercept, 1.0));
+        assert!(close(result.coefficients[0], 2.0) && close(result.coefficients[1], 3.0));
+        assert!(close(result.r_squared, 1.0));
+        assert!(close(result.predict(&[20.0]), 1241.0));
+
+        let growth = exact(|x| 2.0 * (0.5 * x).exp());
+        let result = fit_model(&growth, &["x"], "y", Model::Exponential).unwrap();
+        assert!(close(result.intercept, 2f64.ln()) && close(result.coefficients[0], 0.5));
+        assert!((result.predict(&[4.0]) - 2.0 * 2f64.exp()).abs() < 1e-6);
+
+        let saturation = exact(|x| 1.0 + 3.0 * x.ln());
+        let result = fit_model(&saturation, &["x"], "y", Model::Logarithmic).unwrap();
+        assert_eq!(result.terms, ["ln(x)"]);
+        assert!(close(result.intercept, 1.0) && close(result.coefficients[0], 3.0));
+
+        // the transformed terms need positive values
+        let negative = exact(|x| x - 5.0);
+        assert!(matches!(
+            fit_model(&negative, &["x"], "y", Model::Exponential),
+            Err(Error::Column { .. })
+        ));
+        assert!(matches!(
+            fit_model(&negative, &["x"], "y", Model::Polynomial(0)),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn ranking_by_information_criteria() {
+        // a parabola with a little wiggle, dipping below zero so the exponential model can't fit
+        let df = exact(|x| (x - 3.0).powi(2) - 2.0 + 0.1 * (x * 1.7).sin());
+        let models = [Model::Linear, Model::Exponential, Model::Polynomial(2), Model::Logarithmic];
+
+        for criterion in [Criterion::Aic, Criterion::Bic] {
+            let comparison = compare_models(&df, "x", "y", &models, criterion).unwrap();
+            assert_eq!(comparison.ranking[0].model, Model::Polynomial(2));
+            assert_eq!(comparison.ranking.len(), 3);
+            let scores: Vec<f64> = comparison
+                .ranking
+                .iter()
+                .map(|result| match criterion {
+                    Criterion::Aic => result.aic,
+                    Criterion::Bic => result.bic,
+                })
+                .collect();
+            assert!(scores.windows(2).all(|pair| pair[0] <= pair[1]));
+
+            let [(model, error)] = &comparison.skipped[..] else {
+                panic!("expected only the exponential model to be skipped");
+            };
+            assert_eq!(*model, Model::Exponential);
+            assert_eq!(error.code(), "column");
+        }
+
+        // n ln(RSS / n) + 2k and + k ln n, k counting the intercept
+        let line = fit(&df, &["x"], "y").unwrap();
+        let n = line.y.len() as f64;
+        let rss = line.residuals.mapv(|r| r * r).sum();
+        assert!(close(line.aic, n * (rss / n).ln() + 4.0));
+        assert!(close(line.bic, n * (rss / n).ln() + 2.0 * n.ln()));
+
+        let nothing = compare_models(&df, "x", "y", &[Model::Exponential], Criterion::Aic);
+        assert!(nothing.is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use std::io::Write;
+
+use ndarray::Array2; //cargo add ndarray@0.15 -> 2D arrays, also has 3D etc., linfa breaks with 0.16
+
+use crate::error::Result;
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // basic array - note the predefined length!
+    let mut arr: [i32; 5] = [1, 2, 3, 4, 5];
+
+    // access data
+    writeln!(out, "First: {}", arr[0])?;
+
+    // modify - needs mut!
+    arr[2] = 99;
+
+    // iterate
+    for val in arr.iter() {
+        writeln!(out, "{}", val)?;
+    }
+
+    // expressive way to get sum
+    let arr: [f64; 3] = [1.1, 2.2, 3.3];
+    let sum: f64 = arr.iter().sum();
+    writeln!(out, "Sum = {}", sum)?;
+
+    // strings
+    let greetings: [&str; 3] = ["hello", "hi", "hola"];
+    for s in &greetings {
+        writeln!(out, "Greeting: {}", s)?;
+    }
+
+    // 2d array - matrix
+    let mut a = Array2::<f64>::zeros((3, 3));
+
+    // Set diagonal values to 1
+    for i in 0..3 {
+        a[(i, i)] = 1.0;
+    }
+
+    writeln!(out, "Identity matrix:\n{}", a)?;
+
+    // Vector - array of mutable length
+
+    let mut v: Vec<i32> = Vec::new(); // empty vector of i32
+
+    // Add values
+    v.push(10);
+    v.push(20);
+    v.push(30);
+    writeln!(out, "After pushes: {:?}", v)?;
+
+    // Remove last value
+    if let Some(val) = v.pop() {
+        writeln!(out, "Popped value: {}", val)?;
+    }
+
+    writeln!(out, "After pop: {:?}", v)?;
+
+    for val in &v {
+        //take reverence when iterating over a vector!
+        writeln!(out, "Value: {}", val)?;
+    }
+
+    // vector with strings
+    let mut names: Vec<String> = vec![];
+
+    names.push("Alice".to_string());
+    names.push("Bob".into());
+
+    writeln!(out, "{:?}", names)?;
+
+    names.pop();
+    writeln!(out, "{:?}", names)?;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use std::io::Write;
+
+use crate::digits::reverse_digits;
+use crate::error::Result;
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    writeln!(out, "Hello, crusteceans 🦀!")?; //prints a message to the console, or wherever `out` points
+
+    // variables, casting
+
+    let mut x: i32 = 123; //define: mutable int32, can also infer like let x = 3, but then you miss some specific expressive functions like .abs()
+    x = -x; //swap sign
+    let _unused_variable = 5; // _ means the variable doesn't need to be used so compiler won't warn you
+    writeln!(out, "{}", x)?;
+    let xstr = x.abs().to_string(); //convert to string ignoring the sign
+    let srev = xstr.chars().rev().collect::<String>(); //reverse the string
+    writeln!(out, "{}", srev)?;
+
+    // reversing a number without the String round trip, see digits.rs
+    // parsing srev back with .parse::<i32>() fails for big numbers and you can't tell why, reverse_digits says so
+    let irev = reverse_digits(x, 10)?; //-321, the sign stays
+    writeln!(out, "{}", irev)?;
+    writeln!(out, "{:?}", reverse_digits(0x1f_u8, 16))?; //any radix, Ok(241) = 0xf1
+    match reverse_digits(1_999_999_999_i32, 10) {
+        //9_999_999_991 doesn't fit an i32
+        Ok(num) => writeln!(out, "{}", num)?,
+        Err(e) => writeln!(out, "Error: {}", e)?,
+    }
+
+    // str and String
+
+    let xstr = "str slice"; //str, stored on a stack, immutable fixed size
+    let mut x_string = String::from("proper String"); //String, stored on the heap, can be mutable and expandable (vector like in C++)
+
+    //xstr = xstr + "a"; -> this is not valid! even if you make xstr mut it will tell you to use String instead
+    writeln!(out, "{}", xstr)?;
+    x_string += " oh yeah"; //this works perfectly fine! (same as x_string = x_string + " oh yeah")
+    writeln!(out, "{}", x_string)?;
+
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use std::io::Write;
+use polars::prelude::*; //cargo add polars --features lazy,ndarray -> for dataframes
+
+use crate::error::{Error, Result};
+use crate::{chart, clustering, data, fitting, plotting, preprocessing, theme};
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // clustering
+    // the last row has no y, see the nulls option below
+    let x = vec![1.0, 1.1, 1.2, 5.0, 5.1, 5.2, 9.0, 9.1, 9.2, 3.0];
+    let y = vec![
+        Some(1.0), Some(0.9), Some(1.1),
+        Some(5.0), Some(5.2), Some(5.1),
+        Some(9.0), Some(9.2), Some(8.9),
+        None,
+    ];
+    let z = vec![0.5, 0.7, 0.4, 3.0, 3.2, 2.9, 6.1, 5.8, 6.0, 2.0];
+
+    let df = df![ //make it a DF
+        "x" => &x,
+        "y" => &y,
+        "z" => &z
+    ]?; //a polars error converts into the crate's Error, see error.rs
+
+    let cluster_options = plotting::ClusterPlotOptions {
+        // KMeans with k picked automatically from 2..=6 clusters, use ClusterCount::Fixed(3) to force it
+        algorithm: clustering::Algorithm::KMeans(clustering::ClusterCount::Auto { min: 2, max: 6 }),
+        // rows with a null are left out, NullPolicy::Mean / Median fill them in, NullPolicy::Error stops
+        nulls: data::NullPolicy::Drop,
+        // plot the first two clustered columns, Projection::Pca shows the first two principal components
+        projection: plotting::Projection::Columns(0, 1),
+        // columns as they are, see the customers example below for scaling
+        preprocessing: None,
+        // centre markers, the regions closest to each centre and an outline around each cluster
+        centroids: true,
+        regions: true,
+        hulls: true,
+        // axis ranges come from the data, ChartAxes can add log scales or fixed ranges
+        axes: chart::ChartAxes::default(),
+        // 800x600 PNG, RenderOptions can also change the size, scale or backend (SVG, RGB buffer)
+        render: chart::RenderOptions::new("clusters.png"),
+    };
+
+    // colours come from a theme, the same palette can be exported as CSS variables for a web page
+    let names: Vec<&str> = theme::Theme::ALL.iter().map(|theme| theme.name).collect();
+    writeln!(out, "Themes: {}", names.join(", "))?;
+    if let Some(latte) = theme::Theme::by_name("Latte") {
+        write!(out, "{}", latte.css_variables())?;
+    }
+
+    let (report, rendered) = plotting::plot_dataframe(&df, &["x", "y"], &cluster_options)?; //pulling from a side module mod plotting;
+    writeln!(out, "{report}")?; //the library prints nothing itself, everything it found is in the report
+    writeln!(out, "{}", rendered.describe("clusters"))?;
+
+    // labels back into the DataFrame, then a lazy query per cluster
+    let summary = plotting::with_clusters(&df, &report, true)?
+        .lazy()
+        .group_by([col("cluster")])
+        .agg([
+            len().alias("points"),
+            col("x").mean().alias("mean x"),
+            col("distance").max().alias("max distance"),
+        ])
+        .sort(["cluster"], Default::default())
+        .collect()?;
+    writeln!(out, "{summary}")?;
+
+    // the other algorithms on the same points, cluster_dataframe only clusters and leaves clusters.png alone
+    let algorithms = [
+        clustering::Algorithm::GaussianMixture(clustering::ClusterCount::Fixed(3)),
+        clustering::Algorithm::Agglomerative(
+            clustering::ClusterCount::Auto { min: 2, max: 6 },
+            clustering::Linkage::Ward,
+        ),
+        // points need 2 neighbours within 0.5 to grow a cluster, anything else is noise
+        clustering::Algorithm::Dbscan { tolerance: 0.5, min_points: 2 },
+    ];
+    for algorithm in algorithms {
+        let options = plotting::ClusterPlotOptions {
+            algorithm,
+            ..cluster_options.clone()
+        };
+        let report = plotting::cluster_dataframe(&df, &["x", "y"], &options)?;
+        writeln!(out, "{report}")?;
+    }
+
+    // any number of columns can be clustered, PCA squeezes them into two dimensions for the chart
+    let pca_options = plotting::ClusterPlotOptions {
+        projection: plotting::Projection::Pca,
+        render: chart::RenderOptions {
+            backend: chart::Backend::Rgb,
+            ..chart::RenderOptions::new("clusters.png")
+        },
+        ..cluster_options.clone()
+    };
+    let (report, _) = plotting::plot_dataframe(&df, &["x", "y", "z"], &pca_options)?;
+    writeln!(out, "{report}")?;
+
+    // income is in the thousands and would drown out age in the distances, and the plan is text:
+    // standardise both numbers and one-hot encode the plan before clustering
+    let customers = df![
+        "age" => [23.0, 25.0, 31.0, 35.0, 52.0, 58.0, 61.0, 24.0],
+        "income" => [21_000.0, 24_000.0, 48_000.0, 52_000.0, 75_000.0, 80_000.0, 30_000.0, 23_000.0],
+        "plan" => ["basic", "basic", "plus", "plus", "pro", "pro", "basic", "basic"]
+    ]?;
+    let customer_options = plotting::ClusterPlotOptions {
+        preprocessing: Some(
+            preprocessing::Pipeline::default()
+                .scale(preprocessing::Scaler::Standard, &["age", "income"])
+                .one_hot("plan"),
+        ),
+        ..pca_options.clone()
+    };
+    let columns = ["age", "income", "plan=basic", "plan=plus", "plan=pro"];
+    let (report, _) = plotting::plot_dataframe(&customers, &columns, &customer_options)?;
+    writeln!(out, "{report}")?;
+    // the stored parameters transform new customers exactly like the ones above
+    if let Some(pipeline) = &report.preprocessing {
+        writeln!(out, "{pipeline}")?;
+        let new_customers = df![
+            "age" => [40.0],
+            "income" => [60_000.0],
+            "plan" => ["plus"]
+        ]?;
+        writeln!(out, "{}", pipeline.transform(&new_customers)?)?;
+    }
+
+    // real files instead of df! literals: CSV, Parquet, JSON/NDJSON and IPC, see data.rs
+    // sensor ids keep their leading zeros as strings, hour and humidity would be read as integers
+    let load_options = data::LoadOptions {
+        schema_overrides: vec![
+            ("sensor".to_string(), DataType::String),
+            ("hour".to_string(), DataType::Float64),
+            ("humidity".to_string(), DataType::Float64),
+        ],
+        ..data::LoadOptions::default()
+    };
+    let measurements = data::load_dataframe("data/measurements.csv", &load_options)?;
+    writeln!(out, "{}", measurements.head(Some(3)))?;
+    let weather_options = plotting::ClusterPlotOptions {
+        preprocessing: Some(
+            preprocessing::Pipeline::default()
+                .scale(preprocessing::Scaler::Standard, &["temperature", "humidity"]),
+        ),
+        ..pca_options.clone()
+    };
+    let (report, _) = plotting::plot_dataframe(&measurements, &["temperature", "humidity"], &weather_options)?;
+    writeln!(out, "{report}")?;
+
+    // fitting
+    let x = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+    let y = vec![1.1, 1.9, 3.2, 4.1, 5.0, 6.5, 6.9, 7.6, 8.8];
+
+    let df = df![
+        "x" => &x,
+        "y" => &y
+    ]?;
+
+    // 95% confidence and prediction bands, data driven axes, plus residuals.png
+    let fit_options = fitting::FitPlotOptions {
+        // a straight line, Polynomial(2), Exponential or Logarithmic fit curves
+        model: fitting::Model::Linear,
+        axes: chart::ChartAxes::default(),
+        band_level: Some(0.95),
+        render: chart::RenderOptions::new("fit.png"),
+        residuals: Some(chart::RenderOptions {
+            width: 1500,
+            height: 500,
+            ..chart::RenderOptions::new("residuals.png")
+        }),
+        // e.g. Some(Pipeline::default().scale(Scaler::Standard, &["x"])) to fit on standardised x
+        preprocessing: None,
+    };
+
+    // features first (can be several columns), then the target column
+    let (result, rendered, residuals) = fitting::fit_and_plot(&df, &["x"], "y", &fit_options)?;
+    writeln!(out, "{result}")?; //coefficient report
+    writeln!(out, "{}", rendered.describe("fit"))?;
+    if let Some(residuals) = residuals {
+        writeln!(out, "{}", residuals.describe("residual diagnostics"))?;
+    }
+
+    // several features at once, fitting::fit only returns the numbers without plotting
+    let df = df![
+        "x1" => [1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        "x2" => [0.5, 0.1, 0.9, 0.3, 0.7, 0.2],
+        "y" => [3.1, 4.2, 7.9, 8.1, 11.2, 11.5]
+    ]?;
+
+    let result = fitting::fit(&df, &["x1", "x2"], "y")?;
+    writeln!(out, "{result}")?;
+    writeln!(out, "Residuals: {}", result.residuals)?;
+
+    // errors are an enum, so a caller can match on what went wrong instead of reading the message
+    match fitting::fit_model(&df, &["x1", "x2"], "y", fitting::Model::Polynomial(2)) {
+        Ok(result) => writeln!(out, "{result}")?,
+        Err(Error::InvalidParameter(message)) => writeln!(out, "Not possible: {message}")?,
+        Err(e) => return Err(e), //anything else is a real failure
+    }
+    match fitting::fit(&df, &["x1", "x3"], "y") {
+        Ok(result) => writeln!(out, "{result}")?,
+        Err(e @ Error::Column { .. }) => writeln!(out, "Check the columns ({}): {e}", e.code())?,
+        Err(e) => return Err(e),
+    }
+
+    // curved data: fit several model families and rank them, lowest AIC first
+    let df = df![
+        "t" => [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+        "signal" => [2.7, 3.6, 5.0, 6.6, 9.0, 12.1, 16.3, 22.1]
+    ]?;
+
+    let models = [
+        fitting::Model::Linear,
+        fitting::Model::Polynomial(2),
+        fitting::Model::Polynomial(3),
+        fitting::Model::Exponential,
+        fitting::Model::Logarithmic,
+    ];
+    let comparison = fitting::compare_models(&df, "t", "signal", &models, fitting::Criterion::Aic)?;
+    let ranking = &comparison.ranking;
+    for (rank, result) in ranking.iter().enumerate() {
+        writeln!(
+            out,
+            "{}. {}: AIC = {:.2}, BIC = {:.2}",
+            rank + 1,
+            result.model,
+            result.aic,
+            result.bic
+        )?;
+    }
+    for (model, e) in &comparison.skipped {
+        writeln!(out, "Skipped {model}: {e}")?; //the models that couldn't fit this data, with why
+    }
+    writeln!(out, "Best model:\n{}", ranking[0])?;
+
+    // draw the best curve into memory instead of a file, e.g. to send it in a web response
+    let in_memory = fitting::FitPlotOptions {
+        render: chart::RenderOptions {
+            backend: chart::Backend::Rgb,
+            scale: 2.0, //1600x1200 pixels
+            ..chart::RenderOptions::new("")
+        },
+        ..fitting::FitPlotOptions::default()
+    };
+    if let chart::Rendered::Rgb { pixels, .. } = fitting::plot_fit(&ranking[0], &in_memory)? {
+        writeln!(out, "Buffer holds {} bytes", pixels.len())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::cell::Cell;
+use std::io::Write;
+use std::rc::Rc;
+
+use crate::error::Result;
+use crate::events::{Event, EventBus, EventKind, Modifiers}; //the Event enum lives in events.rs, with a bus to send events through
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // In Rust structs are used to make custom data types with named fields.
+    // Enums, on the other hand, are used to represent a fixed set of possible values.
+    // Structs -> AND
+    // Enums -> OR
+    // Event in events.rs is an enum: Load is simply named, Press and Click carry named data (like a mini-struct)
+
+    // using options in an enum called Event:
+    let key_press_event = Event::press('q');
+    let click_event = Event::Click { x: 100, y: 250 };
+
+    writeln!(out, "Key press event: {:?}", key_press_event)?;
+    writeln!(out, "Click event: {:?}", click_event)?;
+
+    // you can even have an enum of structs etc.
+
+    // match picks the variant apart again, .. skips the fields we don't need
+    let save = Event::Press {
+        key: 's',
+        modifiers: Modifiers::CTRL,
+    };
+    match save {
+        Event::Press { key, modifiers } if !modifiers.is_empty() => {
+            writeln!(out, "Shortcut: {modifiers}+{key}")?
+        }
+        Event::Press { key, .. } => writeln!(out, "Typed: {key}")?,
+        other => writeln!(out, "Something else: {:?}", other)?,
+    }
+
+    // an event bus calls the handlers registered for an event's kind, in the order events were emitted
+    let mut bus = EventBus::new();
+    let clicks = Rc::new(Cell::new(0)); //shared with the handler, Cell so it can change behind an Rc
+    let counter = Rc::clone(&clicks);
+    bus.on(EventKind::Click, move |_| counter.set(counter.get() + 1));
+    // handlers can't reach the bus while it dispatches, an emitter queues their events instead
+    let emitter = bus.emitter();
+    bus.on(EventKind::Resize, move |_| emitter.emit(Event::Load)); //dispatched in the same round
+    bus.record(); //keep what gets dispatched, to replay it later
+    bus.emit(click_event);
+    bus.emit(Event::Resize {
+        width: 800,
+        height: 600,
+    });
+    bus.emit(Event::Click { x: 5, y: 5 });
+    let dispatched = bus.dispatch();
+    writeln!(
+        out,
+        "Dispatched {dispatched} events, {} clicks",
+        clicks.get()
+    )?;
+
+    let log = bus.stop_recording();
+    let json = log.to_json()?; //serde turns the recording into JSON and back
+    writeln!(out, "Replay log is {} bytes of JSON", json.len())?;
+
+    Ok(())
+}
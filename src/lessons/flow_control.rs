@@ -0,0 +1,29 @@
+use std::io::Write;
+
+use crate::error::Result;
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // if else example
+    let value = 10; //initialize a value
+    if value < 5 {
+        //check if value is less than 5
+        writeln!(out, "Value is less than 5")?; //prints if true
+    } else if value < 10 {
+        //check if value is less than 10
+        writeln!(out, "Value is less than 10 but greater than or equal to 5")?; //prints if true
+    } else {
+        writeln!(out, "Value is 10 or greater")?; //prints if none of the above conditions are true
+    }
+
+    // match example
+    let number = 3; //initialize a number
+    match number {
+        //match statement to check the value of number
+        1 => writeln!(out, "Number is one")?, //if number is 1, prints this
+        2 => writeln!(out, "Number is two")?, //if number is 2, prints this
+        3 => writeln!(out, "Number is three")?, //if number is 3, prints this
+        _ => writeln!(out, "Number is something else")?, //if number is anything else, prints this
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::io::Write;
+
+use crate::error::Result;
+use crate::loader::{self, LoadError}; //loading files lives in its own module, see loader.rs
+
+//basic function with error handling
+pub fn count_lines(path: &str) -> Result<usize, LoadError> {
+    //public function, takes pointer to a string, returns the number of lines or error
+    let mut count = 0;
+    for line in loader::lines(path)? {
+        //? at the end of the line denotes this could be an error to handle
+        line?; //every line can fail as well, e.g. a broken byte halfway through the file
+        count += 1;
+    }
+    Ok(count) //lines are read one at a time, the file is never in memory all at once
+}
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // Load a file using a function from the loader module and handle potential errors
+
+    // match case option - controlled flow, returns content variable or e variable
+    writeln!(out, "Loading file...")?;
+    match loader::read_to_string("example.txt") {
+        //attempts to load a file named "example.txt", gzip/zstd and UTF-16/Latin-1 files work too
+        Ok(content) => writeln!(out, "File content: {}", content)?, //if successful, prints the content of the file
+        Err(e) => writeln!(out, "Error loading file: {}", e)?,      //
+    }
+
+    // handle error using the ? operator - shorthand for simple usecases
+    let content2 = count_lines("example.txt"); //counts the lines of the same file
+    writeln!(out, "Lines in example.txt: {:?}", content2)?; //prints the result, which can be Ok or Err
+
+    // the error is an enum, so you can match on what went wrong
+    match count_lines("non_existent_file.txt") {
+        Ok(count) => writeln!(out, "{count} lines")?,
+        Err(LoadError::NotFound(path)) => writeln!(out, "No file at {}", path.display())?,
+        Err(LoadError::Permission(path)) => writeln!(out, "Not allowed to read {}", path.display())?,
+        Err(e) => writeln!(out, "Something else: {}", e)?,
+    }
+
+    // big files: stream them in pieces or map them into memory instead of reading them all at once
+    let lines = loader::lines("example.txt")?;
+    writeln!(out, "Encoding: {:?}, compression: {:?}", lines.encoding(), lines.compression())?;
+    let first_chunk = loader::chunks("example.txt", 16)?.next().transpose()?; //the first 16 bytes as text, if any
+    writeln!(out, "First chunk: {:?}", first_chunk)?;
+    let mapped = loader::map("example.txt")?; //the OS reads the pages when they're touched
+    writeln!(out, "Mapped {} bytes: {:?}", mapped.bytes().len(), mapped.text()?)?;
+
+    // handle error using unwrap_or_else - gives a default value for the target variable
+    let mut failure = None; //the closure can't use ? for the lesson's result, so it keeps the error for later
+    let content3 = loader::read_to_string("non_existent_file.txt").unwrap_or_else(|err| {
+        //attempts to load a non-existent file and handles the error
+        failure = Some(err); //closures can borrow variables from around them, mutably too
+        String::from("Default content") //returns a default string if the file cannot be loaded
+    });
+    if let Some(err) = failure {
+        writeln!(out, "Error: {}", err)?; //prints the error message, back outside the closure ? works again
+    }
+    writeln!(out, "File content3: {}", content3)?; //prints the content, which will be the default string in case of an error
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use std::io::Write;
+use std::rc::Rc; // to use reference counting
+use std::sync::Arc;
+
+use crate::error::Result;
+
+// In functions:
+// The annotation <'a> says: "This function has a generic lifetime 'a'".
+// The signature fn longest<'a>(x: &'a str, y: &'a str) -> &'a str says:
+// "The returned string slice will live at least as long as the SHORTEST of the input slices x and y."
+pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
+
+// A classic example: a recursive list.
+// Without `Box`, this type would have an infinite size.
+// `Box<List>` breaks the cycle because a box is a pointer, which has a known, fixed size.
+// Private to the lesson, the full generic version is crate::list::List.
+#[derive(Debug)]
+enum List {
+    Node(i32, Box<List>),
+    Nil,
+}
+
+// walking it means following the boxes, recursively like the type itself
+fn sum(list: &List) -> i32 {
+    match list {
+        List::Node(value, rest) => value + sum(rest),
+        List::Nil => 0,
+    }
+}
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // Managing how long data lives in Rust is done in a few ways:
+    // - Lifetimes: specify how long data is valid
+    // - Box: allocate data on the heap, as simple as it gets
+    // - Rc: reference counting for shared ownership - multiple references to the same data
+    // - Arc: atomic reference counting for shared ownership across threads
+
+    // lifetimes - usually based on scope, but can be more complex
+    {
+        let lifetime = "lifetime";
+        writeln!(out, "Lifetime: {}", lifetime)?;
+    }
+    //here: writeln!(out, "Lifetime: {}", lifetime)?; -> out of scope!
+
+    // In functions: see longest above
+
+    let string1 = String::from("long string is long");
+    let result;
+    {
+        let string2 = String::from("xyz");
+        // `result` is valid only as long as both `string1` and `string2` are.
+        result = longest(string1.as_str(), string2.as_str());
+        writeln!(out, "The longest string is: {}", result)?;
+    }
+    // Here, `string2` is out of scope. The compiler would prevent us
+    // from using `result` here because its lifetime has ended.
+    // writeln!(out, "{}", result)?; // <-- This would be a compile-time error!
+
+    // box
+    {
+        let box_data = Box::new("box"); //this is stored on the heap
+        writeln!(out, "Box: {}", box_data)?;
+    }
+    //here: writeln!(out, "Box: {}", box_data)?; -> out of scope as well!
+
+    {
+        // the recursive List above
+        // Create a list on the heap: 5 -> 10 -> Nil
+        let list = List::Node(5, Box::new(List::Node(10, Box::new(List::Nil))));
+        // The `list` variable is on the stack, but the node data (5, 10) is on the heap.
+        // When `list` goes out of scope, all heap data is cleaned up recursively.
+        writeln!(out, "List: {:?}, sum: {}", list, sum(&list))?;
+    }
+
+    {
+        // the same idea grown up in list.rs: generic, with push/pop, iterators and a Drop
+        // that doesn't recurse, so a million nodes don't overflow the stack
+        let mut list: crate::list::List<i32> = (1..=3).collect();
+        list.push_front(0);
+        list.reverse();
+        writeln!(out, "Generic list: {:?}, {} long", list, list.len())?;
+    }
+
+    // rc
+    // use std::rc::Rc;
+    let rc_data = Rc::new("rc");
+    writeln!(out, "Rc: {}", rc_data)?;
+
+    {
+        // `data` is allocated on the heap once.
+        let data = Rc::new(String::from("shared data"));
+
+        writeln!(out, "Initial strong count: {}", Rc::strong_count(&data))?; // -> 1
+
+        // `owner1` and `owner2` now both "own" the data.
+        // `Rc::clone` is cheap—it just increases the reference count.
+        let _owner1 = Rc::clone(&data);
+        writeln!(out, "Count after first clone: {}", Rc::strong_count(&data))?; // -> 2
+
+        {
+            let _owner2 = Rc::clone(&data);
+            writeln!(out, "Count inside inner scope: {}", Rc::strong_count(&data))?; // -> 3
+        } // `owner2` goes out of scope, count decreases.
+
+        writeln!(out, "Count after inner scope: {}", Rc::strong_count(&data))?; // -> 2
+    }
+
+    {
+        // Rc lets lists share their tails: `prepend` makes a new list, the old one stays as it was
+        let tail = crate::list::PersistentList::new().prepend(3).prepend(2);
+        let a = tail.prepend(1);
+        let b = tail.prepend(9);
+        writeln!(
+            out,
+            "Shared tail: {:?} and {:?}, same nodes: {}",
+            a,
+            b,
+            a.tail().ptr_eq(&b.tail())
+        )?;
+    }
+
+    // arc
+    let arc_data = Arc::new("arc");
+    writeln!(out, "Arc: {}", arc_data)?;
+
+    {
+        use std::sync::Arc;
+        use std::thread;
+
+        // The data is allocated on the heap once.
+        let data = Arc::new(String::from("thread-safe shared data"));
+
+        // We'll spawn multiple threads, and each will get its own `Arc` pointer.
+        let mut handles = vec![];
+        for i in 0..3 {
+            // `Arc::clone` is cheap, just like `Rc::clone`.
+            let data_clone = Arc::clone(&data);
+
+            // The `move` keyword transfers ownership of `data_clone` into the thread.
+            let handle = thread::spawn(move || format!("Thread {} sees: '{}'", i, data_clone));
+            handles.push(handle);
+        }
+
+        // Wait for all threads to finish.
+        for handle in handles {
+            writeln!(out, "{}", handle.join().unwrap())?;
+        }
+
+        writeln!(out, "Final strong count: {}", Arc::strong_count(&data))?; // -> 1
+    }
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use std::io::Write;
+
+use super::structs::Numberinator;
+use crate::error::Result;
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    let mut numberinator = Numberinator::new(0, String::from("Counting loops.")); //the struct from the STRUCTS lesson
+
+    // Loop over a range
+    for i in 0..5 {
+        //loop from 0 to 4
+        writeln!(out, "Loop iteration: {}", i)?; //prints the current iteration number
+        numberinator.increment(); //increments the number in Numberinator
+        numberinator.display(out)?; //displays the updated number and name
+    }
+
+    // While loop example
+    let mut count = 0; //initialize a counter variable - mutable!
+    while count < 5 {
+        //loop while count is less than 5
+        writeln!(out, "While loop count: {}", count)?; //prints the current count
+        count += 1;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::io::Write;
+
+use crate::error::Result;
+
+// The walkthrough, one lesson per topic. Every lesson writes what it prints to `out`
+// instead of stdout, so it can go to the terminal, to a file or into a String.
+// `cargo run -- lessons list` shows them, `cargo run -- lessons run rng loops` runs some,
+// `cargo run` without arguments runs them all in order.
+
+pub mod arrays;
+pub mod basics;
+pub mod dataframes;
+pub mod enums;
+pub mod flow_control;
+pub mod functions;
+pub mod lifetimes;
+pub mod loops;
+pub mod parallelism;
+pub mod rng;
+pub mod structs;
+
+// what every lesson file exposes as `run`, a plain function pointer since lessons keep no state
+pub type LessonFn = fn(&mut dyn Write) -> Result<()>;
+
+pub struct Lesson {
+    pub name: &'static str,  // what you type on the command line
+    pub title: &'static str, // the header printed above the output
+    pub summary: &'static str,
+    pub run: LessonFn,
+}
+
+// in walkthrough order, later lessons sometimes mention things from earlier ones
+pub const ALL: &[Lesson] = &[
+    Lesson {
+        name: "basics",
+        title: "Variables and strings",
+        summary: "variables, casting, str vs String",
+        run: basics::run,
+    },
+    Lesson {
+        name: "functions",
+        title: "FUNCTIONS",
+        summary: "functions returning Result, match, ? and unwrap_or_else",
+        run: functions::run,
+    },
+    Lesson {
+        name: "structs",
+        title: "STRUCTS",
+        summary: "structs with methods, the Numberinator",
+        run: structs::run,
+    },
+    Lesson {
+        name: "loops",
+        title: "LOOPS",
+        summary: "for and while loops, using the Numberinator",
+        run: loops::run,
+    },
+    Lesson {
+        name: "flow_control",
+        title: "FLOW CONTROL",
+        summary: "if / else and match",
+        run: flow_control::run,
+    },
+    Lesson {
+        name: "rng",
+        title: "RNG",
+        summary: "random numbers and distributions",
+        run: rng::run,
+    },
+    Lesson {
+        name: "arrays",
+        title: "Arrays",
+        summary: "arrays, vectors and ndarray",
+        run: arrays::run,
+    },
+    Lesson {
+        name: "parallelism",
+        title: "Parallelism/Threads",
+        summary: "rayon and manual threads, takes a few seconds",
+        run: parallelism::run,
+    },
+    Lesson {
+        name: "dataframes",
+        title: "DataFrames, fitting, plotting",
+        summary: "polars, clustering, preprocessing and fitting, writes charts",
+        run: dataframes::run,
+    },
+    Lesson {
+        name: "enums",
+        title: "Enums vs Structs",
+        summary: "enums with data, matching on them and sending them through an event bus",
+        run: enums::run,
+    },
+    Lesson {
+        name: "lifetimes",
+        title: "Lifetimes, Box, RC, Arc",
+        summary: "lifetimes, Box, Rc and Arc across threads",
+        run: lifetimes::run,
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static Lesson> {
+    ALL.iter().find(|lesson| lesson.name.eq_ignore_ascii_case(name))
+}
+
+impl Lesson {
+    // the header block the walkthrough always printed, then the lesson itself
+    pub fn run(&self, out: &mut dyn Write) -> Result<()> {
+        writeln!(out)?;
+        writeln!(out, "###########")?;
+        writeln!(out, "{}", self.title)?;
+        writeln!(out)?;
+        (self.run)(out)
+    }
+
+    // runs the lesson and hands back everything it printed
+    pub fn capture(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        self.run(&mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned()) //lessons only write text, lossy never loses anything
+    }
+}
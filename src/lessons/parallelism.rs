@@ -0,0 +1,76 @@
+use std::io::Write;
+use rand::Rng;
+use rayon::join; //cargo add rayon -> divide and conquer algorithm approach
+use rayon::prelude::*; //cargo add rayon -> parallelism
+use std::thread; //for manual threading of full functions
+use std::time::Duration; //so we can sleep set amount of time
+
+use crate::error::Result;
+
+// parallelised function with divide and conquer approach
+// None for an empty slice, like Iterator::min
+pub fn parallel_min(slice: &[i32]) -> Option<i32> {
+    const THRESHOLD: usize = 10;
+
+    if slice.len() <= THRESHOLD {
+        //if small enough problem: do serially
+        slice.iter().min().copied()
+    } else {
+        let mid = slice.len() / 2; //split in half
+        let (left, right) = slice.split_at(mid);
+
+        let (min_left, min_right) = join(
+            //run on parts of the problem in parallel and join results
+            || parallel_min(left),
+            || parallel_min(right),
+        );
+
+        min_left.min(min_right) //both halves have values here, so no None sneaks in
+    }
+}
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // Rayon - for applying on collections, slices, ranges, vectors, etc.
+    let data = vec![1, 2, 3, 4, 5];
+    writeln!(out, "Data: {:?}", &data)?;
+    writeln!(out, "Performing x * x in parallel.")?;
+    let results: Vec<_> = data.par_iter().map(|x| x * x).collect();
+    writeln!(out, "Results: {:?}", &results)?;
+
+    let mut v = vec![5, 3, 8, 1, 2];
+    writeln!(out, "Vector to sort in parallel: {:?}", &v)?;
+    v.par_sort_unstable(); // uses multiple threads
+    writeln!(out, "Sorted: {:?}", v)?;
+
+    let sum: i32 = (1..=100).into_par_iter().sum(); //this can thread panic if you exceed max stack size!
+    writeln!(out, "Parallel sum: {}", sum)?;
+
+    let max = (1..=100).into_par_iter().max().unwrap();
+    writeln!(out, "Parallel max: {}", max)?;
+
+    let data: Vec<i32> = (1..=100).rev().collect();
+    writeln!(out, "Vector to find the minimum of in parallel: {:?}", &data)?;
+
+    let min_value = parallel_min(&data); //splits too large a task into subtasks that run in parallel, and joins the results
+    writeln!(out, "Min value: {:?}", min_value)?;
+
+    // std::thread - for manually parallelising running functions
+
+    //normal function to multithread - `out` can't be shared between threads, so it returns what it has to say
+    fn task(name: &str) -> String {
+        let mut rng = rand::rng();
+        let sleep_secs = rng.random_range(0.5..=3.0);
+        thread::sleep(Duration::from_secs_f64(sleep_secs));
+        format!("{name} slept for {sleep_secs:.2} seconds, finished!")
+    }
+
+    let handle1 = thread::spawn(|| task("Task 1"));
+    let handle2 = thread::spawn(|| task("Task 2"));
+
+    writeln!(out, "{}", handle1.join().unwrap())?; //join hands back the return value of the thread
+    writeln!(out, "{}", handle2.join().unwrap())?;
+
+    writeln!(out, "Both tasks complete.")?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::io::Write;
+use ndarray::Array2;
+use rand::Rng; //cargo add rand -> random numbers
+use rand_distr::{Distribution, Normal}; //cargo add rand_distr -> random distributions
+
+use crate::error::Result;
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // Just RNG
+    let mut rng = rand::rng(); //instantiate random numer generator
+    let num: u32 = rng.random_range(0..=10); // inclusive range 0 to 10
+    writeln!(out, "Random number between 0 and 10: {}", num)?;
+
+    // RNG sample from normal distribution
+    let normal = Normal::new(0.0, 1.0).unwrap(); // mean = 0, std dev = 1
+    let mut rng2 = rand::rng();
+    let sample = normal.sample(&mut rng2);
+    writeln!(out, "Random sample from N(0,1): {}", sample)?;
+
+    // RNG distribution into an ndarray::Array2
+    let mut rng3 = rand::rng();
+    let normal = Normal::new(0.0, 1.0).unwrap(); // mean = 0, std dev = 1
+    let rows = 3;
+    let cols = 4;
+    // Generate a 2D array of normal-distributed samples
+    let data: Vec<f64> = (0..rows * cols).map(|_| normal.sample(&mut rng3)).collect();
+
+    let array: Array2<f64> = ndarray::Array2::from_shape_vec((rows, cols), data)?; //a wrong shape is an error, not a panic
+    writeln!(out, "Generated normal-distributed array:\n{array}")?;
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use std::io::Write;
+
+use crate::error::Result;
+
+//basic struct - Rust custom data type that can hold multiple values
+pub struct Numberinator {
+    pub number: i32,  //public field to hold a number
+    pub name: String, //public field to hold a name
+}
+
+impl Numberinator {
+    //implementation block for Numberinator struct - OOP stuff
+    pub fn new(number: i32, name: String) -> Self {
+        //constructor function to create a new instance of Numberinator
+        Numberinator { number, name } //returns a new instance with the provided number and name
+    }
+
+    pub fn display(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        //method to display the number and name - takes the place to write to, the console or a file
+        writeln!(out, "Number: {}, Name: {}", self.number, self.name) //prints the number and name
+    }
+
+    pub fn increment(&mut self) {
+        //method to increment the number - mutable reference to self since it modifies the value
+        self.number += 1; //increments the number by 1
+    }
+}
+
+pub fn run(out: &mut dyn Write) -> Result<()> {
+    // Create an instance of the defined struct and demonstrate its functionality
+    let mut numberinator = Numberinator::new(42, String::from("Initially 42.")); //creates a new instance of Numberinator with number 42 and name "Forty-Two" - mutable so we can change its values
+    numberinator.display(out)?; //calls the display method to print the number and name
+    numberinator.increment(); //calls the increment method to increase the number by 1
+    numberinator.display(out)?; //calls the display method again to show the updated number and name
+
+    Ok(())
+}
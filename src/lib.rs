@@ -0,0 +1,31 @@
+// The library half of the crate: everything the walkthrough and the command line use, so other
+// crates (and the tests) can depend on it, e.g. rust-syntax-revision = { path = "../crate" }
+// and then use rust_syntax_revision::fitting, ::plotting, ::loader...
+// main.rs only parses the command line and calls cli::run.
+
+pub mod chart; //axes, render targets and the Figure trait shared by the charts
+pub mod cli; //the fit, cluster and lessons subcommands
+pub mod clustering; //KMeans, GMM, agglomerative and DBSCAN with their quality metrics
+pub mod data; //f64 columns, null handling and reading CSV/Parquet/JSON/IPC into DataFrames
+pub mod digits; //reverse_digits
+pub mod error; //the crate wide Error and Result
+pub mod events; //the Event enum from the enums lesson with an event bus, timestamps and replay logs
+pub mod fitting; //linear, polynomial, exponential and logarithmic regressions with plots
+pub mod lessons; //the walkthrough, a folder with a mod.rs is a module too, each lesson is a file inside it
+pub mod list; //the List enum from the lifetimes lesson as a generic linked list, owned and persistent
+pub mod loader; //streaming, memory mapped and decompressing text file loading
+pub mod plotting; //cluster plots
+pub mod preprocessing; //scaling and one-hot encoding pipelines
+pub mod stats; //distributions and linear algebra the fits and PCA need
+pub mod theme; //colour palettes for the charts and the web examples
+
+pub use error::{Error, Result};
+
+// the small examples from the walkthrough, at the top so they're easy to import
+pub use events::{Event, EventBus};
+pub use lessons::functions::count_lines;
+pub use lessons::lifetimes::longest;
+pub use lessons::parallelism::parallel_min;
+pub use lessons::structs::Numberinator;
+pub use list::{List, PersistentList};
+pub use loader::read_to_string as load;
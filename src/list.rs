@@ -0,0 +1,477 @@
+use std::fmt;
+use std::iter::FusedIterator;
+use std::mem;
+use std::rc::Rc;
+
+// The recursive List enum from the lifetimes lesson grown into a usable singly linked list.
+// `List<T>` owns its nodes (Box), `PersistentList<T>` shares them (Rc): prepending to it or taking
+// its tail gives a new list and leaves the old one untouched, both use the same nodes.
+// Rust's own VecDeque is nearly always faster, this is about how ownership works in a list.
+
+// the lesson's enum, generic: a value and the rest of the list, or the end
+enum Cons<T> {
+    Node(T, Box<Cons<T>>),
+    Nil,
+}
+
+pub struct List<T> {
+    head: Cons<T>,
+    len: usize,
+}
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List {
+            head: Cons::Nil,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        // the old head moves into the new node, mem::replace leaves Nil behind meanwhile
+        let rest = mem::replace(&mut self.head, Cons::Nil);
+        self.head = Cons::Node(value, Box::new(rest));
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        match mem::replace(&mut self.head, Cons::Nil) {
+            Cons::Node(value, rest) => {
+                self.head = *rest;
+                self.len -= 1;
+                Some(value)
+            }
+            Cons::Nil => None,
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        match &self.head {
+            Cons::Node(value, _) => Some(value),
+            Cons::Nil => None,
+        }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        match &mut self.head {
+            Cons::Node(value, _) => Some(value),
+            Cons::Nil => None,
+        }
+    }
+
+    // turns the list around in place, no node is allocated or copied
+    pub fn reverse(&mut self) {
+        let mut rest = mem::replace(&mut self.head, Cons::Nil);
+        let mut reversed = Cons::Nil;
+        while let Cons::Node(value, mut next) = rest {
+            rest = mem::replace(&mut *next, reversed);
+            reversed = Cons::Node(value, next); //the same Box, now pointing backwards
+        }
+        self.head = reversed;
+    }
+
+    // moves all of `other` to the end of this list, leaving `other` empty
+    // walks to the end first, so it takes as long as this list is
+    pub fn append(&mut self, other: &mut List<T>) {
+        let mut end = &mut self.head;
+        while let Cons::Node(_, next) = end {
+            end = next;
+        }
+        *end = mem::replace(&mut other.head, Cons::Nil);
+        self.len += mem::take(&mut other.len);
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: &self.head,
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: Some(&mut self.head),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        List::new()
+    }
+}
+
+// the default drop would drop the first node, which drops the second... one stack frame per
+// node, a long enough list overflows the stack. Unlinking the nodes in a loop keeps it flat.
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut rest = mem::replace(&mut self.head, Cons::Nil);
+        while let Cons::Node(_, mut next) = rest {
+            rest = mem::replace(&mut *next, Cons::Nil);
+        }
+    }
+}
+
+// written out instead of derived, the derives would recurse through the nodes like Drop
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// keeps the order of the iterator: collect pushes to the front, then turns the list around
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        for value in iter {
+            list.push_front(value);
+        }
+        list.reverse();
+        list
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: &'a Cons<T>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next {
+            Cons::Node(value, rest) => {
+                self.next = rest;
+                self.len -= 1;
+                Some(value)
+            }
+            Cons::Nil => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> FusedIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut Cons<T>>, // taken out while stepping, so the &mut is never held twice
+    len: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next.take()? {
+            Cons::Node(value, rest) => {
+                self.next = Some(rest);
+                self.len -= 1;
+                Some(value)
+            }
+            Cons::Nil => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+// the values themselves, popped off the front one by one
+pub struct IntoIter<T>(List<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// An immutable list whose nodes are shared: `prepend` and `tail` return new lists in O(1)
+// and never change the one they were called on, so old versions stay valid (persistent).
+//   let a = [2, 3] -> b = a.prepend(1) is [1, 2, 3] and c = a.prepend(9) is [9, 2, 3],
+//   all three use the same 2 and 3 nodes.
+pub struct PersistentList<T> {
+    head: Option<Rc<Node<T>>>, // Rc so several lists can point at the same node
+    len: usize,
+}
+
+struct Node<T> {
+    value: T,
+    next: Option<Rc<Node<T>>>,
+}
+
+impl<T> PersistentList<T> {
+    pub fn new() -> Self {
+        PersistentList { head: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // a new list with the value in front of this one
+    pub fn prepend(&self, value: T) -> Self {
+        PersistentList {
+            head: Some(Rc::new(Node {
+                value,
+                next: self.head.clone(), //only the pointer is cloned, the count goes up
+            })),
+            len: self.len + 1,
+        }
+    }
+
+    // everything after the first value, an empty list stays empty
+    pub fn tail(&self) -> Self {
+        PersistentList {
+            head: self.head.as_ref().and_then(|node| node.next.clone()),
+            len: self.len.saturating_sub(1),
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    // true if both lists start at the very same node, not just equal values
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn iter(&self) -> PersistentIter<'_, T> {
+        PersistentIter {
+            next: self.head.as_deref(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for PersistentList<T> {
+    fn default() -> Self {
+        PersistentList::new()
+    }
+}
+
+// cheap: the copy shares every node
+impl<T> Clone for PersistentList<T> {
+    fn clone(&self) -> Self {
+        PersistentList {
+            head: self.head.clone(),
+            len: self.len,
+        }
+    }
+}
+
+// nodes are only freed once no other list uses them, Rc::try_unwrap tells us when we're the
+// last owner. As soon as a node is still shared the rest of the list is too, and we can stop.
+impl<T> Drop for PersistentList<T> {
+    fn drop(&mut self) {
+        let mut next = self.head.take();
+        while let Some(node) = next {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => next = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for PersistentList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for PersistentList<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// same order as the iterator, the values are collected first as a list can only grow at the front
+impl<T> FromIterator<T> for PersistentList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let values: Vec<T> = iter.into_iter().collect();
+        values
+            .into_iter()
+            .rev()
+            .fold(PersistentList::new(), |list, value| list.prepend(value))
+    }
+}
+
+pub struct PersistentIter<'a, T> {
+    next: Option<&'a Node<T>>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for PersistentIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = node.next.as_deref();
+        self.len -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for PersistentIter<'_, T> {}
+impl<T> FusedIterator for PersistentIter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a PersistentList<T> {
+    type Item = &'a T;
+    type IntoIter = PersistentIter<'a, T>;
+
+    fn into_iter(self) -> PersistentIter<'a, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_pop_and_iterate() {
+        let mut list = List::new();
+        assert_eq!(list.pop_front(), None);
+        list.push_front(3);
+        list.push_front(2);
+        list.push_front(1);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.front(), Some(&1));
+
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [10, 20, 30]);
+        assert_eq!(format!("{list:?}"), "[10, 20, 30]");
+        assert_eq!(list.pop_front(), Some(10));
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), [20, 30]);
+    }
+
+    #[test]
+    fn collect_reverse_append() {
+        let mut list: List<i32> = (1..=4).collect();
+        list.reverse();
+        assert_eq!(list, (1..=4).rev().collect());
+
+        let mut other: List<i32> = (5..=6).collect();
+        list.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [4, 3, 2, 1, 5, 6]);
+
+        let mut empty = List::new();
+        empty.append(&mut list);
+        assert_eq!(empty.len(), 6);
+        assert_eq!(empty.clone(), empty);
+    }
+
+    #[test]
+    fn long_lists_drop_without_overflowing() {
+        let list: List<u32> = (0..1_000_000).collect();
+        assert_eq!(list.iter().len(), 1_000_000);
+        drop(list);
+
+        let persistent: PersistentList<u32> = (0..1_000_000).collect();
+        let shared = persistent.tail();
+        drop(persistent);
+        assert_eq!(shared.len(), 999_999);
+    }
+
+    #[test]
+    fn persistent_versions_share_nodes() {
+        let base: PersistentList<i32> = [2, 3].into_iter().collect();
+        let one = base.prepend(1);
+        let nine = base.prepend(9);
+        assert_eq!(
+            format!("{one:?} {nine:?} {base:?}"),
+            "[1, 2, 3] [9, 2, 3] [2, 3]"
+        );
+        assert!(one.tail().ptr_eq(&base));
+        assert!(nine.tail().ptr_eq(&one.tail()));
+        assert_eq!(one.head(), Some(&1));
+        assert_eq!(base.tail().tail().tail(), PersistentList::new());
+    }
+}
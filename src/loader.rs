@@ -0,0 +1,599 @@
+use encoding_rs::{Decoder, Encoding, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252}; //cargo add encoding_rs -> text encodings
+use flate2::read::MultiGzDecoder; //cargo add flate2 -> gzip
+use memmap2::Mmap; //cargo add memmap2 -> memory mapped files
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+// Loading text files without assuming they're small or UTF-8.
+// The first bytes tell us how the file is compressed (gzip, zstd or not at all) and, after
+// decompressing, how the text is encoded (a BOM for UTF-8 or UTF-16, otherwise UTF-8 if it
+// decodes, Latin-1 if it doesn't). Without a BOM the streaming readers start out as UTF-8 and
+// switch to Latin-1 at the first byte that isn't, however far into the file.
+// `lines` and `chunks` stream the file in small pieces, `map` memory-maps it so the OS pages
+// it in as needed, `read_to_string` just gets it all.
+
+// what went wrong, not found and permission problems are told apart from broken contents
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error("{} does not exist", .0.display())]
+    NotFound(PathBuf),
+    #[error("no permission to read {}", .0.display())]
+    Permission(PathBuf),
+    // the bytes aren't valid for the encoding or compression the file claims
+    #[error("could not decode {}: {reason}", path.display())]
+    Decode {
+        path: PathBuf,
+        reason: String,
+        #[source]
+        source: Option<io::Error>, // the decompressor's error, None for bad text
+    },
+    // anything else the OS complains about
+    #[error("reading {} failed", path.display())]
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl LoadError {
+    // sorts an io error by what the caller can do about it
+    pub fn from_io(path: &Path, error: io::Error) -> Self {
+        let path = path.to_path_buf();
+        match error.kind() {
+            io::ErrorKind::NotFound => LoadError::NotFound(path),
+            io::ErrorKind::PermissionDenied => LoadError::Permission(path),
+            // flate2 and zstd report corrupt streams as invalid data
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => LoadError::Decode {
+                path,
+                reason: "the compressed data is broken".to_string(),
+                source: Some(error), //the decompressor says what exactly
+            },
+            _ => LoadError::Io {
+                path,
+                source: error,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    // from the magic bytes at the start of the file, the extension can lie
+    fn sniff(start: &[u8]) -> Self {
+        if start.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if start.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1, // decoded as Windows-1252, the superset browsers use for "latin1" too
+}
+
+impl TextEncoding {
+    // a BOM decides, without one it's UTF-8 if the sample decodes and Latin-1 if it doesn't
+    // (returned as a guess, a later byte can still turn out not to be UTF-8, see utf8_then_latin1),
+    // `complete` says the sample is the whole file, otherwise a cut off character at the end is fine
+    fn sniff(sample: &[u8], complete: bool) -> (Self, Option<usize>) {
+        if let Some((encoding, bom_length)) = Encoding::for_bom(sample) {
+            return (TextEncoding::from_encoding(encoding), Some(bom_length));
+        }
+        let encoding = match std::str::from_utf8(sample) {
+            Ok(_) => TextEncoding::Utf8,
+            // error_len None means the sample ends halfway through a character
+            Err(e) if e.error_len().is_none() && !complete => TextEncoding::Utf8,
+            Err(_) => TextEncoding::Latin1,
+        };
+        (encoding, None)
+    }
+
+    fn from_encoding(encoding: &'static Encoding) -> Self {
+        if encoding == UTF_16LE {
+            TextEncoding::Utf16Le
+        } else if encoding == UTF_16BE {
+            TextEncoding::Utf16Be
+        } else {
+            TextEncoding::Utf8
+        }
+    }
+
+    fn encoding(self) -> &'static Encoding {
+        match self {
+            TextEncoding::Utf8 => UTF_8,
+            TextEncoding::Utf16Le => UTF_16LE,
+            TextEncoding::Utf16Be => UTF_16BE,
+            TextEncoding::Latin1 => WINDOWS_1252,
+        }
+    }
+}
+
+// how much of the file is looked at to guess the encoding
+const SNIFF_BYTES: usize = 8 * 1024;
+
+// The rule for files without a BOM whose first SNIFF_BYTES are UTF-8: UTF-8 up to the first byte
+// that isn't, Latin-1 from there on. Streaming and MappedFile::text both decode with it.
+// Every byte is a Latin-1 character, so this can't fail
+fn utf8_then_latin1(bytes: &[u8]) -> Cow<'_, str> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Cow::Borrowed(text),
+        Err(e) => {
+            let (valid, rest) = bytes.split_at(e.valid_up_to());
+            let mut text = String::from(std::str::from_utf8(valid).expect("valid up to here"));
+            text.push_str(&WINDOWS_1252.decode_without_bom_handling(rest).0);
+            Cow::Owned(text)
+        }
+    }
+}
+
+// a decompressed, decoded stream of text, `lines` and `chunks` sit on top of it
+pub struct TextReader {
+    path: PathBuf,
+    reader: Box<dyn BufRead>, // a trait object so plain, gzip and zstd files are the same type
+    decoder: Decoder,
+    encoding: TextEncoding,
+    compression: Compression,
+    guessed_utf8: bool, // no BOM, so UTF-8 only until a byte says otherwise
+    carry: Vec<u8>,     // the start of a UTF-8 character split over two reads, while guessing
+    offset: usize,      // decompressed bytes consumed so far, for error messages
+    finished: bool,
+}
+
+impl TextReader {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let error = |e| LoadError::from_io(path, e);
+
+        let mut raw = BufReader::new(File::open(path).map_err(error)?);
+        let compression = Compression::sniff(raw.fill_buf().map_err(error)?);
+        let mut reader: Box<dyn BufRead> = match compression {
+            Compression::None => Box::new(raw),
+            Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(raw))),
+            Compression::Zstd => Box::new(BufReader::new(
+                zstd::Decoder::with_buffer(raw).map_err(error)?, //cargo add zstd -> zstd
+            )),
+        };
+
+        // read a sample to sniff the encoding from, then put it back in front of the rest
+        let mut sample = Vec::with_capacity(SNIFF_BYTES);
+        (&mut reader)
+            .take(SNIFF_BYTES as u64)
+            .read_to_end(&mut sample)
+            .map_err(error)?;
+        let (encoding, bom) = TextEncoding::sniff(&sample, sample.len() < SNIFF_BYTES);
+        let bom_length = bom.unwrap_or(0);
+        let mut sample = io::Cursor::new(sample);
+        sample.set_position(bom_length as u64); //the BOM isn't part of the text
+        let reader = Box::new(sample.chain(reader));
+
+        Ok(TextReader {
+            path: path.to_path_buf(),
+            reader,
+            decoder: encoding.encoding().new_decoder_without_bom_handling(),
+            encoding,
+            compression,
+            guessed_utf8: bom.is_none() && encoding == TextEncoding::Utf8,
+            carry: Vec::new(),
+            offset: bom_length,
+            finished: false,
+        })
+    }
+
+    pub fn encoding(&self) -> TextEncoding {
+        self.encoding
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    // the next piece of text, decoded from at most `max_bytes` bytes of the (decompressed) file
+    pub fn next_chunk(&mut self, max_bytes: usize) -> Option<Result<String, LoadError>> {
+        if self.finished {
+            return None;
+        }
+        let input = match self.reader.fill_buf() {
+            Ok(input) => input,
+            Err(e) => {
+                self.finished = true;
+                return Some(Err(LoadError::from_io(&self.path, e)));
+            }
+        };
+        let input = &input[..input.len().min(max_bytes.max(4))]; //never empty, an empty input means the end of the file
+        let last = input.is_empty();
+
+        let (text, read) = if self.guessed_utf8 {
+            let mut bytes = std::mem::take(&mut self.carry);
+            bytes.extend_from_slice(input);
+            let text = match std::str::from_utf8(&bytes) {
+                Ok(text) => text.to_owned(),
+                // a character cut off at the end of the read, keep its start for the next one
+                Err(e) if e.error_len().is_none() && !last => {
+                    self.carry = bytes.split_off(e.valid_up_to());
+                    String::from_utf8(bytes).expect("valid up to valid_up_to")
+                }
+                // not UTF-8 after all: Latin-1 from here on, what was read so far stays as it was
+                Err(_) => {
+                    let text = utf8_then_latin1(&bytes).into_owned();
+                    self.guessed_utf8 = false;
+                    self.encoding = TextEncoding::Latin1;
+                    self.decoder = WINDOWS_1252.new_decoder_without_bom_handling();
+                    text
+                }
+            };
+            (text, input.len())
+        } else {
+            // with room for the worst case the decoder always takes the whole input
+            let mut text = String::with_capacity(
+                self.decoder
+                    .max_utf8_buffer_length_without_replacement(input.len())
+                    .unwrap_or(input.len() * 3),
+            );
+            let (result, read) = self
+                .decoder
+                .decode_to_string_without_replacement(input, &mut text, last);
+            if let encoding_rs::DecoderResult::Malformed(..) = result {
+                self.finished = true;
+                return Some(Err(LoadError::Decode {
+                    path: self.path.clone(),
+                    reason: format!(
+                        "invalid {} near byte {}",
+                        self.decoder.encoding().name(),
+                        self.offset + read
+                    ),
+                    source: None,
+                }));
+            }
+            (text, read)
+        };
+        self.reader.consume(read);
+        self.offset += read;
+        self.finished = last;
+
+        // an empty piece in the middle just means a character is split over two reads
+        if text.is_empty() && !last {
+            return self.next_chunk(max_bytes);
+        }
+        (!text.is_empty()).then_some(Ok(text))
+    }
+}
+
+// pieces of decoded text, each from at most `size` bytes of the file
+pub struct Chunks {
+    reader: TextReader,
+    size: usize,
+}
+
+impl Iterator for Chunks {
+    type Item = Result<String, LoadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next_chunk(self.size)
+    }
+}
+
+// lines without their \n or \r\n, read a chunk at a time
+pub struct Lines {
+    reader: TextReader,
+    pending: String,
+    scanned: usize, // how much of pending is known to have no \n, so long lines aren't searched again and again
+}
+
+impl Lines {
+    // can change from Utf8 to Latin1 while reading, see TextReader
+    pub fn encoding(&self) -> TextEncoding {
+        self.reader.encoding()
+    }
+
+    pub fn compression(&self) -> Compression {
+        self.reader.compression()
+    }
+}
+
+impl Iterator for Lines {
+    type Item = Result<String, LoadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(end) = self.pending[self.scanned..].find('\n') {
+                let mut line: String = self.pending.drain(..=self.scanned + end).collect();
+                self.scanned = 0;
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+                return Some(Ok(line));
+            }
+            self.scanned = self.pending.len();
+            match self.reader.next_chunk(SNIFF_BYTES) {
+                Some(Ok(text)) => self.pending.push_str(&text),
+                Some(Err(e)) => return Some(Err(e)),
+                // the last line may not end in a newline
+                None if self.pending.is_empty() => return None,
+                None => {
+                    self.scanned = 0;
+                    return Some(Ok(std::mem::take(&mut self.pending)));
+                }
+            }
+        }
+    }
+}
+
+pub fn lines(path: impl AsRef<Path>) -> Result<Lines, LoadError> {
+    Ok(Lines {
+        reader: TextReader::open(path)?,
+        pending: String::new(),
+        scanned: 0,
+    })
+}
+
+pub fn chunks(path: impl AsRef<Path>, size: usize) -> Result<Chunks, LoadError> {
+    Ok(Chunks {
+        reader: TextReader::open(path)?,
+        size,
+    })
+}
+
+// the whole file as one String, decompressed and decoded
+pub fn read_to_string(path: impl AsRef<Path>) -> Result<String, LoadError> {
+    let mut reader = TextReader::open(path)?;
+    let mut text = String::new();
+    while let Some(chunk) = reader.next_chunk(64 * 1024) {
+        text.push_str(&chunk?);
+    }
+    Ok(text)
+}
+
+// a file mapped into memory, nothing is read until the bytes are touched
+pub struct MappedFile {
+    path: PathBuf,
+    map: Option<Mmap>, // empty files can't be mapped
+}
+
+pub fn map(path: impl AsRef<Path>) -> Result<MappedFile, LoadError> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| LoadError::from_io(path, e))?;
+    let length = file
+        .metadata()
+        .map_err(|e| LoadError::from_io(path, e))?
+        .len();
+    // safety: the map is only read, and it's undefined behaviour if another process truncates
+    // the file meanwhile, the usual caveat of memory mapping
+    let map = if length == 0 {
+        None
+    } else {
+        Some(unsafe { Mmap::map(&file) }.map_err(|e| LoadError::from_io(path, e))?)
+    };
+    Ok(MappedFile {
+        path: path.to_path_buf(),
+        map,
+    })
+}
+
+impl MappedFile {
+    pub fn bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or_default()
+    }
+
+    // borrowed straight from the map for uncompressed UTF-8, a new String otherwise
+    pub fn text(&self) -> Result<Cow<'_, str>, LoadError> {
+        let bytes = self.bytes();
+        let decompressed: Cow<[u8]> = match Compression::sniff(bytes) {
+            Compression::None => Cow::Borrowed(bytes),
+            Compression::Gzip => {
+                let mut buffer = Vec::new();
+                MultiGzDecoder::new(bytes)
+                    .read_to_end(&mut buffer)
+                    .map_err(|e| LoadError::from_io(&self.path, e))?;
+                Cow::Owned(buffer)
+            }
+            Compression::Zstd => {
+                Cow::Owned(zstd::decode_all(bytes).map_err(|e| LoadError::from_io(&self.path, e))?)
+            }
+        };
+
+        // the same sample as the streaming readers look at, so both decode a file the same way
+        let sample = &decompressed[..decompressed.len().min(SNIFF_BYTES)];
+        let (encoding, bom) = TextEncoding::sniff(sample, decompressed.len() <= SNIFF_BYTES);
+        if bom.is_none() && encoding == TextEncoding::Utf8 {
+            return Ok(match decompressed {
+                Cow::Borrowed(bytes) => utf8_then_latin1(bytes),
+                Cow::Owned(bytes) => Cow::Owned(utf8_then_latin1(&bytes).into_owned()),
+            });
+        }
+        let bom_length = bom.unwrap_or(0);
+        let invalid = || LoadError::Decode {
+            path: self.path.clone(),
+            reason: format!("invalid {}", encoding.encoding().name()),
+            source: None,
+        };
+        match decompressed {
+            Cow::Borrowed(bytes) => encoding
+                .encoding()
+                .decode_without_bom_handling_and_without_replacement(&bytes[bom_length..])
+                .ok_or_else(invalid),
+            Cow::Owned(bytes) => encoding
+                .encoding()
+                .decode_without_bom_handling_and_without_replacement(&bytes[bom_length..])
+                .map(|text| Cow::Owned(text.into_owned()))
+                .ok_or_else(invalid),
+        }
+    }
+}
+
+// test files for the tests here and in data.rs
+#[cfg(test)]
+pub(crate) mod test_files {
+    use std::path::PathBuf;
+
+    // a file in the temp folder, removed again when the test is done,
+    // the name has to be unique among all the crate's tests as they run in parallel
+    pub(crate) struct TempFile(pub(crate) PathBuf);
+
+    impl TempFile {
+        pub(crate) fn new(name: &str, bytes: &[u8]) -> Self {
+            let path = std::env::temp_dir().join(format!("test-{}-{name}", std::process::id()));
+            std::fs::write(&path, bytes).unwrap();
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_files::TempFile;
+    use super::*;
+    use std::io::Write;
+
+    fn all_lines(path: &Path) -> Vec<String> {
+        lines(path).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn encodings() {
+        let utf8 = TempFile::new("utf8", "\u{feff}héllo\r\nwörld".as_bytes());
+        assert_eq!(all_lines(&utf8.0), ["héllo", "wörld"]);
+
+        let mut utf16: Vec<u8> = vec![0xff, 0xfe];
+        utf16.extend("héllo\nwörld\n".encode_utf16().flat_map(u16::to_le_bytes));
+        let utf16 = TempFile::new("utf16", &utf16);
+        assert_eq!(lines(&utf16.0).unwrap().encoding(), TextEncoding::Utf16Le);
+        assert_eq!(all_lines(&utf16.0), ["héllo", "wörld"]);
+        assert_eq!(map(&utf16.0).unwrap().text().unwrap(), "héllo\nwörld\n");
+
+        let latin1 = TempFile::new("latin1", b"caf\xe9\n");
+        assert_eq!(all_lines(&latin1.0), ["café"]);
+        assert_eq!(read_to_string(&latin1.0).unwrap(), "café\n");
+    }
+
+    #[test]
+    fn latin1_after_the_sniffed_part() {
+        // the first non-UTF-8 byte comes after the sample the encoding is guessed from
+        let mut bytes = "a".repeat(SNIFF_BYTES + 1000).into_bytes();
+        bytes.extend(b"\ncaf\xe9\nna\xefve");
+        let file = TempFile::new("late-latin1", &bytes);
+
+        let mut lines = lines(&file.0).unwrap();
+        assert_eq!(lines.encoding(), TextEncoding::Utf8);
+        assert_eq!(lines.next().unwrap().unwrap().len(), SNIFF_BYTES + 1000);
+        assert_eq!(lines.next().unwrap().unwrap(), "café");
+        assert_eq!(lines.encoding(), TextEncoding::Latin1);
+        assert_eq!(lines.next().unwrap().unwrap(), "naïve");
+        assert!(lines.next().is_none());
+
+        let text = read_to_string(&file.0).unwrap();
+        assert_eq!(text, map(&file.0).unwrap().text().unwrap());
+        assert!(text.ends_with("café\nnaïve"));
+
+        // UTF-8 before the stray byte stays UTF-8, whichever way the file is read
+        let mut bytes = "é\n".repeat(SNIFF_BYTES).into_bytes();
+        bytes.extend(b"caf\xe9");
+        let file = TempFile::new("utf8-then-latin1", &bytes);
+        let text = read_to_string(&file.0).unwrap();
+        assert_eq!(text, map(&file.0).unwrap().text().unwrap());
+        assert_eq!(text, "é\n".repeat(SNIFF_BYTES) + "café");
+        let read = super::lines(&file.0).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!((read[0].as_str(), read[SNIFF_BYTES].as_str()), ("é", "café"));
+
+        // but inside the sample it makes the whole file Latin-1, the same both ways
+        let file = TempFile::new("latin1-in-sample", b"\xc3\xa9\ncaf\xe9");
+        let text = read_to_string(&file.0).unwrap();
+        assert_eq!(text, map(&file.0).unwrap().text().unwrap());
+        assert_eq!(text, "Ã©\ncafé");
+
+        // a real multi-byte UTF-8 character split between two chunks stays UTF-8
+        let split = TempFile::new("split", "é".repeat(SNIFF_BYTES).as_bytes());
+        assert_eq!(
+            chunks(&split.0, 5)
+                .unwrap()
+                .collect::<Result<String, _>>()
+                .unwrap(),
+            "é".repeat(SNIFF_BYTES)
+        );
+    }
+
+    #[test]
+    fn long_lines() {
+        let line = "x".repeat(200_000);
+        let file = TempFile::new("long", format!("{line}\n{line}").as_bytes());
+        assert_eq!(all_lines(&file.0), [line.clone(), line]);
+    }
+
+    #[test]
+    fn compressed() {
+        let text = "line\n".repeat(10_000);
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(text.as_bytes()).unwrap();
+        let gzip = TempFile::new("gz", &gzip.finish().unwrap());
+        assert_eq!(read_to_string(&gzip.0).unwrap(), text);
+        assert_eq!(lines(&gzip.0).unwrap().compression(), Compression::Gzip);
+
+        let zstd = TempFile::new("zst", &zstd::encode_all(text.as_bytes(), 0).unwrap());
+        assert_eq!(lines(&zstd.0).unwrap().count(), 10_000);
+        assert_eq!(map(&zstd.0).unwrap().text().unwrap(), text);
+
+        let broken = TempFile::new("broken", &[0x1f, 0x8b, 0, 1, 2, 3]);
+        assert!(matches!(
+            read_to_string(&broken.0),
+            Err(LoadError::Decode {
+                source: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn chunks_split_characters_safely() {
+        let text = "ünïcödé ".repeat(1000);
+        let file = TempFile::new("chunks", text.as_bytes());
+        let pieces: Vec<String> = chunks(&file.0, 7)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(pieces.len() > 1);
+        assert_eq!(pieces.concat(), text);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            read_to_string("no/such/file.txt"),
+            Err(LoadError::NotFound(_))
+        ));
+        assert!(matches!(
+            map("no/such/file.txt"),
+            Err(LoadError::NotFound(_))
+        ));
+
+        let mut odd: Vec<u8> = vec![0xfe, 0xff, 0xd8, 0x00]; //UTF-16BE with half a surrogate pair
+        odd.extend([0x00, 0x41]);
+        let odd = TempFile::new("odd", &odd);
+        assert!(matches!(
+            read_to_string(&odd.0),
+            Err(LoadError::Decode { .. })
+        ));
+
+        let empty = TempFile::new("empty", b"");
+        assert_eq!(read_to_string(&empty.0).unwrap(), "");
+        assert_eq!(map(&empty.0).unwrap().text().unwrap(), "");
+        assert_eq!(lines(&empty.0).unwrap().count(), 0);
+    }
+}
@@ -1,67 +1,534 @@
 use polars::prelude::*; //cargo add polars --features lazy,ndarray -> for dataframes
-use linfa::prelude::*; //cargo add linfa -> for scientific operations
-use linfa_clustering::KMeans; //cargo add linfa-clustering -> for KMeans
-use ndarray::{Array1, Array2}; //cargo add ndarray@0.15 -> linfa breaks with 0.16
+use ndarray::Array2; //cargo add ndarray@0.15 -> linfa breaks with 0.16
+use plotters::coord::Shift;
 use plotters::prelude::*; //cargo add plotters -> for plotting
-use std::error::Error;
 
-pub fn plot_dataframe(df: &DataFrame) -> Result<(), Box<dyn Error>> {
-    // Convert columns to flat Vec<f64>
-    let x = df.column("x")?.f64()?; //get data from x column
-    let y = df.column("y")?.f64()?; //get data from y colunn
+use crate::chart::{self, Axis, ChartAxes, Figure, RenderOptions, Rendered};
+use crate::clustering::{self, Algorithm, ClusterCount, ClusterReport, Clustering};
+use crate::data::{self, NullPolicy};
+use crate::error::{Error, Result};
+use crate::preprocessing::Pipeline;
+use crate::stats;
 
-    let flat: Vec<f64> = x.into_iter()
-        .zip(y)
-        .filter_map(|(ox, oy)| Some((ox?, oy?)))
-        .flat_map(|(x, y)| vec![x, y])
+// which two dimensions the scatter plot shows when clustering over more columns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Columns(usize, usize), // two of the clustered columns, by position
+    Pca,                   // the first two principal components of all clustered columns
+}
+
+// how plot_dataframe clusters and draws
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterPlotOptions {
+    pub algorithm: Algorithm,
+    pub nulls: NullPolicy,
+    pub projection: Projection,
+    pub preprocessing: Option<Pipeline>, // rescale or encode columns before clustering, see preprocessing.rs
+    pub centroids: bool, // mark the cluster centres
+    pub regions: bool,   // shade the area closest to each centre (the Voronoi regions)
+    pub hulls: bool,     // outline the convex hull of each cluster
+    pub axes: ChartAxes,
+    pub render: RenderOptions,
+}
+
+impl Default for ClusterPlotOptions {
+    fn default() -> Self {
+        ClusterPlotOptions {
+            algorithm: Algorithm::KMeans(ClusterCount::Fixed(3)),
+            nulls: NullPolicy::Drop,
+            projection: Projection::Columns(0, 1),
+            preprocessing: None,
+            centroids: true,
+            regions: false,
+            hulls: true,
+            axes: ChartAxes::default(),
+            render: RenderOptions::new("clusters.png"),
+        }
+    }
+}
+
+// clusters the points given by the columns, draws them as set in the options
+// and returns the quality metrics of the clustering
+pub fn plot_dataframe(
+    df: &DataFrame,
+    columns: &[&str],
+    options: &ClusterPlotOptions,
+) -> Result<(ClusterReport, Rendered)> {
+    let (mut report, records, clustering) = cluster_records(df, columns, options)?;
+
+    // Plotting, in two of the columns or the first two principal components
+    let view = View::new(&records, &clustering.centroids, columns, options.projection)?;
+    let figure = ClusterFigure {
+        view: &view,
+        clustering: &clustering,
+        options,
+    };
+    let rendered = chart::render(&figure, &options.render)?;
+
+    report.explained_variance = view.explained;
+    Ok((report, rendered))
+}
+
+// Same clustering as plot_dataframe without drawing anything, for tests and services that only
+// want the labels and the metrics. Only the algorithm, nulls and preprocessing options are used.
+pub fn cluster_dataframe(
+    df: &DataFrame,
+    columns: &[&str],
+    options: &ClusterPlotOptions,
+) -> Result<ClusterReport> {
+    let (report, _, _) = cluster_records(df, columns, options)?;
+    Ok(report)
+}
+
+// the report along with the records and the clustering the chart is drawn from
+fn cluster_records(
+    df: &DataFrame,
+    columns: &[&str],
+    options: &ClusterPlotOptions,
+) -> Result<(ClusterReport, Array2<f64>, Clustering)> {
+    // rescaled first so no column dominates the distances just by its magnitude
+    let (fitted, scaled) = match &options.preprocessing {
+        Some(pipeline) => {
+            let (fitted, scaled) = pipeline.fit_transform(df)?;
+            (Some(fitted), Some(scaled))
+        }
+        None => (None, None),
+    };
+    let frame = scaled.as_ref().unwrap_or(df);
+
+    // the columns as rows of an Array2, nulls dropped or filled in as the options say, see data.rs
+    let records = data::records(frame, columns, options.nulls)?;
+    let imputed = records.imputed;
+    let rows = records.rows; //DataFrame row of every record, to map the labels back
+    let records = records.values;
+
+    // KMeans, GMM, agglomerative or DBSCAN, with a fixed k or a sweep over a range of k, see clustering.rs
+    let clustering = clustering::cluster(&records, &options.algorithm)?;
+
+    // the scores of every k, the noise and the nulls are in the report, nothing is printed here
+    let mut report = ClusterReport::new(&records, &clustering, &options.algorithm, &rows, df.height());
+    report.preprocessing = fitted;
+    report.imputed = imputed;
+    Ok((report, records, clustering))
+}
+
+// A copy of the DataFrame with the cluster of every row appended as a `cluster` column, null for noise
+// and rows left out for nulls. With `distance` set a `distance` column to the cluster centre is added too.
+pub fn with_clusters(
+    df: &DataFrame,
+    report: &ClusterReport,
+    distance: bool,
+) -> Result<DataFrame> {
+    if report.labels.len() != df.height() {
+        return Err(Error::shape(format!(
+            "report has labels for {} rows, the DataFrame has {}",
+            report.labels.len(),
+            df.height()
+        )));
+    }
+
+    let labels: Vec<Option<u32>> = report
+        .labels
+        .iter()
+        .map(|label| label.map(|l| l as u32))
         .collect();
+    let mut columns = vec![Column::new("cluster".into(), labels)];
+    if distance {
+        columns.push(Column::new("distance".into(), report.distances.clone()));
+    }
+    Ok(df.hstack(&columns)?)
+}
+
+// the points and centres in the two dimensions the chart shows
+struct View {
+    points: Array2<f64>,
+    centroids: Array2<f64>,
+    x_desc: String,
+    y_desc: String,
+    explained: Option<f64>, // share of the variance kept by the PCA projection
+}
 
-    let n = flat.len() / 2;
-    let records: Array2<f64> = Array2::from_shape_vec((n, 2), flat)?;
-    let targets: Array1<usize> = Array1::zeros(n);
-
-    let dataset = DatasetBase::new(records.view(), targets.view());
-
-    let model = KMeans::params(3).fit(&dataset)?;
-    let preds = model.predict(&dataset);
-
-    // Plotting
-
-    // base colours
-    let base = RGBColor(30, 30, 46);
-    let text = RGBColor(205, 214, 244);
-    let grid = RGBColor(108, 112, 134);
-
-    // cool colours for clusters
-    let cluster_colours = [
-        RGBColor(250, 179, 135), // Peach
-        RGBColor(203, 166, 247), // Mauve
-        RGBColor(137, 220, 235), // Sky
-    ];
-
-    let root = BitMapBackend::new("clusters.png", (800, 600)).into_drawing_area();
-    root.fill(&base)?;
-    let mut chart = ChartBuilder::on(&root)
-        .caption("KMeans Clustering", ("sans-serif", 42).into_font().color(&text))
-        .margin(20)
-        .x_label_area_size(30)
-        .y_label_area_size(30)
-        .build_cartesian_2d(0.0..10.0, 0.0..10.0)?;
-
-    chart
-        .configure_mesh()
-        .axis_style(&text)
-        .light_line_style(&grid)
-        .label_style(("sans-serif", 20).into_font().color(&text)) //set axis font size here
-        .draw()?;
-    
-    let xvals = df.column("x")?.f64()?.into_no_null_iter();
-    let yvals = df.column("y")?.f64()?.into_no_null_iter();
-
-    for ((x, y), &cluster) in xvals.zip(yvals).zip(preds.iter()) {
-        chart.draw_series(std::iter::once(Circle::new((x, y), 5, cluster_colours[cluster].filled())))?;
+impl View {
+    fn new(
+        records: &Array2<f64>,
+        centroids: &Array2<f64>,
+        columns: &[&str],
+        projection: Projection,
+    ) -> Result<View> {
+        match projection {
+            Projection::Columns(x, y) => {
+                let (Some(x_desc), Some(y_desc)) = (columns.get(x), columns.get(y)) else {
+                    return Err(Error::invalid(format!(
+                        "cannot plot columns {x} and {y}, only {} columns were clustered",
+                        columns.len()
+                    )));
+                };
+                Ok(View {
+                    points: records.select(ndarray::Axis(1), &[x, y]),
+                    centroids: centroids.select(ndarray::Axis(1), &[x, y]),
+                    x_desc: x_desc.to_string(),
+                    y_desc: y_desc.to_string(),
+                    explained: None,
+                })
+            }
+            Projection::Pca => {
+                if records.ncols() < 2 || records.nrows() < 2 {
+                    return Err(Error::shape("PCA needs at least two columns and two rows"));
+                }
+                // eigenvectors of the covariance matrix are the principal directions,
+                // the eigenvalues the variance along each of them
+                let Some(mean) = records.mean_axis(ndarray::Axis(0)) else {
+                    return Err(Error::shape("PCA needs at least one row"));
+                };
+                let centred = records - &mean;
+                let covariance = centred.t().dot(&centred) / (records.nrows() - 1) as f64;
+                let (variances, directions) = stats::symmetric_eigen(&covariance);
+                let components = directions.select(ndarray::Axis(1), &[0, 1]);
+
+                let total: f64 = variances.sum();
+                let share = |i: usize| if total > 0.0 { variances[i] / total } else { 0.0 };
+                Ok(View {
+                    points: centred.dot(&components),
+                    centroids: (centroids - &mean).dot(&components),
+                    x_desc: format!("PC1 ({:.1}%)", 100.0 * share(0)),
+                    y_desc: format!("PC2 ({:.1}%)", 100.0 * share(1)),
+                    explained: Some(share(0) + share(1)),
+                })
+            }
+        }
     }
+}
+
+// everything the clusters chart needs
+struct ClusterFigure<'a> {
+    view: &'a View,
+    clustering: &'a Clustering,
+    options: &'a ClusterPlotOptions,
+}
+
+impl Figure for ClusterFigure<'_> {
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        options: &RenderOptions,
+    ) -> Result<()>
+    where
+        DB::ErrorType: 'static,
+    {
+        let px = |size: u32| options.px(size); //sizes below are for scale 1.0
+
+        // base colours from the theme, see theme.rs
+        let theme = &options.theme;
+        let base = theme.palette.base;
+        let text = theme.palette.text;
+        let grid = theme.palette.overlay0;
+        let noise = theme.palette.overlay2;
+
+        // axis extents from the data and the centres, see chart.rs
+        let labels = &self.clustering.labels;
+        let points = &self.view.points;
+        let centroids = &self.view.centroids;
+        let axes = &self.options.axes;
+        let xs = points.column(0).into_iter().chain(centroids.column(0));
+        let ys = points.column(1).into_iter().chain(centroids.column(1));
+        let x_axis = Axis::fit(xs.copied(), &axes.x)?;
+        let y_axis = Axis::fit(ys.copied(), &axes.y)?;
+
+        let mut caption = format!(
+            "{} Clustering (k = {}",
+            self.options.algorithm, self.clustering.selection.k
+        );
+        match labels.iter().filter(|label| label.is_none()).count() {
+            0 => {}
+            noise => caption += &format!(", {noise} noise"),
+        }
+        if let Some(explained) = self.view.explained {
+            caption += &format!(", PCA {:.1}% of variance", 100.0 * explained);
+        }
+        caption += ")";
+
+        // long captions (PCA, agglomerative) get a smaller font so they fit the width
+        let caption_size = (options.width * 2 / caption.chars().count().max(1) as u32).min(42);
+
+        root.fill(&base)?;
+        let mut chart = ChartBuilder::on(root)
+            .caption(
+                caption,
+                ("sans-serif", px(caption_size)).into_font().color(&text),
+            )
+            .margin(px(20))
+            .x_label_area_size(px(40))
+            .y_label_area_size(px(40))
+            .build_cartesian_2d(x_axis.range.clone(), y_axis.range.clone())?;
+
+        chart
+            .configure_mesh()
+            .axis_style(text)
+            .light_line_style(grid)
+            .label_style(("sans-serif", px(20)).into_font().color(&text)) //set axis font size here
+            .x_label_formatter(&|v| x_axis.label(*v))
+            .y_label_formatter(&|v| y_axis.label(*v))
+            .x_desc(&self.view.x_desc)
+            .y_desc(&self.view.y_desc)
+            .draw()?;
+
+        // every cell of a grid over the chart gets the colour of its nearest centre,
+        // that is exactly how KMeans assigns points (for other algorithms or under PCA it's an approximation)
+        if self.options.regions && centroids.nrows() > 0 {
+            const CELLS: usize = 100;
+            let (x_range, y_range) = (&x_axis.range, &y_axis.range);
+            let cell_width = (x_range.end - x_range.start) / CELLS as f64;
+            let cell_height = (y_range.end - y_range.start) / CELLS as f64;
+            let mut cells = Vec::with_capacity(CELLS * CELLS);
+            for i in 0..CELLS {
+                for j in 0..CELLS {
+                    let x0 = x_range.start + i as f64 * cell_width;
+                    let y0 = y_range.start + j as f64 * cell_height;
+                    let centre = [
+                        x_axis.to_data(x0 + cell_width / 2.0),
+                        y_axis.to_data(y0 + cell_height / 2.0),
+                    ];
+                    let cluster = nearest(centroids, centre);
+                    let colour = theme.accent(cluster).mix(0.15);
+                    cells.push(Rectangle::new(
+                        [(x0, y0), (x0 + cell_width, y0 + cell_height)],
+                        colour.filled(),
+                    ));
+                }
+            }
+            chart.draw_series(cells)?;
+        }
+
+        // hulls are taken in chart coordinates so they stay convex on log axes
+        if self.options.hulls {
+            for cluster in 0..centroids.nrows() {
+                let members: Vec<(f64, f64)> = points
+                    .rows()
+                    .into_iter()
+                    .zip(labels)
+                    .filter(|(_, label)| **label == Some(cluster))
+                    .map(|(record, _)| (x_axis.to_chart(record[0]), y_axis.to_chart(record[1])))
+                    .collect();
+                let mut hull = convex_hull(members);
+                if hull.len() < 2 {
+                    continue; //a single point has nothing to outline
+                }
+                hull.push(hull[0]); //close the outline
+                let style = theme.accent(cluster).stroke_width(px(2));
+                // one element per edge, thick paths get long spikes at the sharp corners of thin hulls
+                let edges = hull.windows(2).map(|edge| PathElement::new(edge.to_vec(), style));
+                chart.draw_series(edges)?;
+            }
+        }
+
+        // the same rows the model saw, so every point gets its own label
+        for (record, &cluster) in points.rows().into_iter().zip(labels) {
+            let point = (x_axis.to_chart(record[0]), y_axis.to_chart(record[1]));
+            match cluster {
+                Some(cluster) => {
+                    let colour = theme.accent(cluster); //wraps around if there are more clusters than colours
+                    chart.draw_series(std::iter::once(Circle::new(point, px(5), colour.filled())))?;
+                }
+                // noise points (DBSCAN only) get a grey cross instead of a cluster colour
+                None => {
+                    let style = noise.stroke_width(px(2));
+                    chart.draw_series(std::iter::once(Cross::new(point, px(5), style)))?;
+                }
+            }
+        }
 
-    println!("✅ Saved to clusters.png");
-    Ok(())
-}
\ No newline at end of file
+        // centres on top: a bigger dot in the cluster colour with an outline in the text colour
+        if self.options.centroids {
+            for (cluster, centroid) in centroids.rows().into_iter().enumerate() {
+                let point = (x_axis.to_chart(centroid[0]), y_axis.to_chart(centroid[1]));
+                let marker = EmptyElement::at(point)
+                    + Circle::new((0, 0), px(9), theme.accent(cluster).filled())
+                    + Circle::new((0, 0), px(9), text.stroke_width(px(2)));
+                chart.draw_series(std::iter::once(marker))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// index of the centroid closest to the point
+fn nearest(centroids: &Array2<f64>, point: [f64; 2]) -> usize {
+    centroids
+        .rows()
+        .into_iter()
+        .map(|c| (c[0] - point[0]).powi(2) + (c[1] - point[1]).powi(2))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(i, _)| i)
+}
+
+// Convex hull by Andrew's monotone chain, counter-clockwise without repeating the first point.
+fn convex_hull(mut points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // > 0 when o -> a -> b turns left
+    let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+
+    let mut hull: Vec<(f64, f64)> = Vec::with_capacity(2 * points.len());
+    // lower half left to right, then upper half right to left
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for p in pass {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop(); //the last point starts the other half
+    }
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn clusters_without_drawing() {
+        // two groups and a row without y, which is dropped and gets no label
+        let df = df![
+            "x" => [0.0, 0.1, 0.2, 5.0, 5.1, 5.2, 3.0],
+            "y" => [Some(0.0), Some(0.2), Some(0.1), Some(5.0), Some(5.2), Some(5.1), None]
+        ]
+        .unwrap();
+        let options = ClusterPlotOptions {
+            algorithm: Algorithm::KMeans(ClusterCount::Fixed(2)),
+            ..ClusterPlotOptions::default()
+        };
+        let report = cluster_dataframe(&df, &["x", "y"], &options).unwrap();
+        assert_eq!(report.labels.len(), 7);
+        assert_eq!((report.dropped, report.labels[6]), (1, None));
+        assert_ne!(report.labels[0], report.labels[3]);
+        assert_eq!(report.labels[..3], [report.labels[0]; 3]);
+        assert_eq!(report.sizes, [3, 3]);
+        assert_eq!(report.explained_variance, None);
+
+        // the same numbers as the chart's report
+        let render = RenderOptions {
+            backend: chart::Backend::Rgb,
+            ..RenderOptions::new("")
+        };
+        let options = ClusterPlotOptions { render, ..options };
+        let (plotted, _) = plot_dataframe(&df, &["x", "y"], &options).unwrap();
+        assert_eq!(plotted.labels, report.labels);
+        assert_eq!(plotted.silhouette, report.silhouette);
+    }
+
+    #[test]
+    fn labels_back_into_the_dataframe() {
+        let df = df![
+            "x" => [0.0, 0.1, 0.2, 5.0, 5.1, 5.2, 3.0],
+            "y" => [Some(0.0), Some(0.2), Some(0.1), Some(5.0), Some(5.2), Some(5.1), None]
+        ]
+        .unwrap();
+        let options = ClusterPlotOptions {
+            algorithm: Algorithm::KMeans(ClusterCount::Fixed(2)),
+            ..ClusterPlotOptions::default()
+        };
+        let report = cluster_dataframe(&df, &["x", "y"], &options).unwrap();
+
+        let labelled = with_clusters(&df, &report, true).unwrap();
+        assert_eq!(labelled.get_column_names(), ["x", "y", "cluster", "distance"]);
+        let clusters = labelled.column("cluster").unwrap().u32().unwrap();
+        let clusters: Vec<Option<u32>> = clusters.into_iter().collect();
+        let expected: Vec<Option<u32>> =
+            report.labels.iter().map(|label| label.map(|l| l as u32)).collect();
+        assert_eq!(clusters, expected);
+        assert_eq!(clusters[6], None); //the row with a null
+        // the first group's centre is about (0.1, 0.1)
+        let distance = labelled.column("distance").unwrap().f64().unwrap();
+        assert!((distance.get(0).unwrap() - 0.02f64.sqrt()).abs() < 1e-4);
+        assert_eq!(distance.get(6), None);
+
+        // ready for a lazy query per cluster, the unlabelled row groups under null
+        let sizes = labelled
+            .lazy()
+            .group_by([col("cluster")])
+            .agg([len().alias("points")])
+            .sort(["cluster"], Default::default())
+            .collect()
+            .unwrap();
+        let points = sizes.column("points").unwrap().u32().unwrap();
+        assert_eq!(points.into_iter().collect::<Vec<_>>(), [Some(1), Some(3), Some(3)]);
+
+        let without = with_clusters(&df, &report, false).unwrap();
+        assert_eq!(without.width(), 3);
+        let error = with_clusters(&df.head(Some(3)), &report, false).unwrap_err();
+        assert_eq!(error.code(), "shape");
+    }
+
+    #[test]
+    fn hull_of_points() {
+        // corners counter-clockwise from the bottom left, the inside point, the one on an edge
+        // and the duplicate corner are left out
+        let square = vec![
+            (2.0, 2.0), (1.0, 1.0), (0.0, 0.0), (2.0, 0.0), (1.0, 0.0), (0.0, 2.0), (2.0, 2.0),
+        ];
+        assert_eq!(convex_hull(square), [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+
+        // points on a line: only the two ends are left
+        let line = vec![(1.0, 1.0), (3.0, 3.0), (0.0, 0.0), (2.0, 2.0)];
+        assert_eq!(convex_hull(line), [(0.0, 0.0), (3.0, 3.0)]);
+
+        // too few for a polygon, they come back as they are (sorted, without duplicates)
+        assert_eq!(convex_hull(vec![(1.0, 2.0)]), [(1.0, 2.0)]);
+        assert_eq!(convex_hull(vec![(3.0, 1.0), (1.0, 2.0)]), [(1.0, 2.0), (3.0, 1.0)]);
+        assert_eq!(convex_hull(vec![(1.0, 2.0), (1.0, 2.0), (1.0, 2.0)]), [(1.0, 2.0)]);
+        assert!(convex_hull(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn nearest_centre() {
+        let centroids = array![[0.0, 0.0], [10.0, 0.0], [5.0, 9.0]];
+        assert_eq!(nearest(&centroids, [1.0, 1.0]), 0);
+        assert_eq!(nearest(&centroids, [9.0, -3.0]), 1);
+        assert_eq!(nearest(&centroids, [5.0, 6.0]), 2);
+        assert_eq!(nearest(&centroids, [5.0, 0.0]), 0); //halfway between two, the first wins
+    }
+
+    #[test]
+    fn pca_view() {
+        // uncorrelated columns with variances 8/5, 2/5 and 2/5: PC1 is the first column,
+        // and the first two components keep 10 of the 12 parts of the variance
+        let records = array![
+            [2.0, 0.0, 0.0],
+            [-2.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, -1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, -1.0]
+        ];
+        let centroids = array![[1.0, 0.0, 0.0]];
+        let view = View::new(&records, &centroids, &["a", "b", "c"], Projection::Pca).unwrap();
+        let explained = view.explained.unwrap();
+        assert!((explained - 10.0 / 12.0).abs() < 1e-12);
+        assert_eq!(view.x_desc, "PC1 (66.7%)");
+        assert!((view.points[(0, 0)].abs() - 2.0).abs() < 1e-12);
+        assert!((view.centroids[(0, 0)].abs() - 1.0).abs() < 1e-12);
+
+        // points on a line are all variance along PC1
+        let line = array![[0.0, 0.0], [1.0, 2.0], [2.0, 4.0], [3.0, 6.0]];
+        let view = View::new(&line, &line, &["x", "y"], Projection::Pca).unwrap();
+        assert!((view.explained.unwrap() - 1.0).abs() < 1e-12);
+        assert!(view.points.column(1).iter().all(|v| v.abs() < 1e-9));
+
+        let one_column = array![[1.0], [2.0]];
+        assert!(View::new(&one_column, &one_column, &["x"], Projection::Pca).is_err());
+        let columns = Projection::Columns(0, 2);
+        let view = View::new(&records, &centroids, &["a", "b", "c"], columns).unwrap();
+        assert_eq!((view.x_desc.as_str(), view.explained), ("a", None));
+    }
+}
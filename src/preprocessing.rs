@@ -0,0 +1,322 @@
+use polars::prelude::*; //cargo add polars --features lazy,ndarray -> for dataframes
+use std::fmt;
+
+use crate::data;
+use crate::error::{Error, Result};
+
+// Preprocessing before clustering or fitting: columns are rescaled or encoded step by step.
+// `Pipeline::fit` learns the parameters (means, ranges, categories) from one DataFrame and the
+// resulting `FittedPipeline` applies exactly the same transform to any other DataFrame later on.
+
+// how a numeric column is rescaled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scaler {
+    Standard, // (x - mean) / standard deviation
+    MinMax,   // (x - min) / (max - min), into 0..1
+    Robust,   // (x - median) / interquartile range, outliers barely move it
+    Log,      // ln(1 + x), squeezes long tails, needs x > -1
+}
+
+// one preprocessing step
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Scale(Scaler, Vec<String>), // rescale these f64 columns
+    OneHot(String), // replace a string column by one 0/1 column per category, e.g. colour=red
+}
+
+// the steps in the order they run
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pipeline {
+    pub steps: Vec<Step>,
+}
+
+impl Pipeline {
+    pub fn scale(mut self, scaler: Scaler, columns: &[&str]) -> Self {
+        let columns = columns.iter().map(|c| c.to_string()).collect();
+        self.steps.push(Step::Scale(scaler, columns));
+        self
+    }
+
+    pub fn one_hot(mut self, column: &str) -> Self {
+        self.steps.push(Step::OneHot(column.to_string()));
+        self
+    }
+
+    // learns the parameters of every step from the DataFrame, each step sees the output of the previous one
+    pub fn fit(&self, df: &DataFrame) -> Result<FittedPipeline> {
+        let mut df = df.clone();
+        let mut fitted = FittedPipeline::default();
+        for step in &self.steps {
+            let new_steps = match step {
+                Step::Scale(scaler, columns) => columns
+                    .iter()
+                    .map(|column| fit_scaler(&df, *scaler, column))
+                    .collect::<Result<Vec<_>, _>>()?,
+                Step::OneHot(column) => vec![fit_one_hot(&df, column)?],
+            };
+            for step in new_steps {
+                df = step.apply(&df)?;
+                fitted.steps.push(step);
+            }
+        }
+        Ok(fitted)
+    }
+
+    // fit and transform the same DataFrame in one go
+    pub fn fit_transform(
+        &self,
+        df: &DataFrame,
+    ) -> Result<(FittedPipeline, DataFrame)> {
+        let fitted = self.fit(df)?;
+        let transformed = fitted.transform(df)?;
+        Ok((fitted, transformed))
+    }
+}
+
+// a step with its learned parameters
+#[derive(Debug, Clone, PartialEq)]
+pub enum FittedStep {
+    // (x - shift) / scale, covers the standard, min-max and robust scalers
+    Affine {
+        column: String,
+        scaler: Scaler,
+        shift: f64,
+        scale: f64,
+    },
+    Log {
+        column: String,
+    },
+    OneHot {
+        column: String,
+        categories: Vec<String>, // sorted, categories not seen while fitting get all zeros
+    },
+}
+
+impl FittedStep {
+    fn apply(&self, df: &DataFrame) -> Result<DataFrame> {
+        let mut df = df.clone();
+        match self {
+            FittedStep::Affine {
+                column,
+                shift,
+                scale,
+                ..
+            } => {
+                let values = data::f64_column(&df, column)?.apply_values(|v| (v - shift) / scale);
+                df.with_column(values.into_series())?;
+            }
+            FittedStep::Log { column } => {
+                let values = data::f64_column(&df, column)?;
+                if let Some(bad) = values.into_iter().flatten().find(|&v| v <= -1.0) {
+                    return Err(Error::column(
+                        column,
+                        format!("needs values > -1 for a log transform, got {bad}"),
+                    ));
+                }
+                let values = values.apply_values(f64::ln_1p);
+                df.with_column(values.into_series())?;
+            }
+            FittedStep::OneHot { column, categories } => {
+                let values = df.column(column)?.str()?.clone();
+                for category in categories {
+                    let name = format!("{column}={category}");
+                    let indicator: Float64Chunked = values
+                        .into_iter()
+                        .map(|value| value.map(|v| if v == category { 1.0 } else { 0.0 }))
+                        .collect();
+                    df.with_column(indicator.with_name(name.into()).into_series())?;
+                }
+                df.drop_in_place(column)?;
+            }
+        }
+        Ok(df)
+    }
+}
+
+impl fmt::Display for FittedStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FittedStep::Affine {
+                column,
+                scaler,
+                shift,
+                scale,
+            } => write!(
+                f,
+                "{column}: {scaler:?} scaling, ({column} - {shift:.4}) / {scale:.4}"
+            ),
+            FittedStep::Log { column } => write!(f, "{column}: ln(1 + {column})"),
+            FittedStep::OneHot { column, categories } => {
+                write!(f, "{column}: one-hot over {}", categories.join(", "))
+            }
+        }
+    }
+}
+
+// the learned steps, apply them to new data with `transform`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FittedPipeline {
+    pub steps: Vec<FittedStep>,
+}
+
+impl FittedPipeline {
+    pub fn transform(&self, df: &DataFrame) -> Result<DataFrame> {
+        let mut df = df.clone();
+        for step in &self.steps {
+            df = step.apply(&df)?;
+        }
+        Ok(df)
+    }
+}
+
+impl fmt::Display for FittedPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Preprocessing:")?;
+        for step in &self.steps {
+            write!(f, "\n  {step}")?;
+        }
+        Ok(())
+    }
+}
+
+fn fit_scaler(df: &DataFrame, scaler: Scaler, column: &str) -> Result<FittedStep> {
+    let values = data::f64_column(df, column)?;
+    let missing = || Error::column(column, format!("has no values to fit {scaler:?} scaling on"));
+
+    let (shift, scale) = match scaler {
+        Scaler::Log => {
+            return Ok(FittedStep::Log {
+                column: column.to_string(),
+            });
+        }
+        Scaler::Standard => (
+            values.mean().ok_or_else(missing)?,
+            values.std(1).unwrap_or(0.0),
+        ),
+        Scaler::MinMax => {
+            let min = values.min().ok_or_else(missing)?;
+            let max = values.max().ok_or_else(missing)?;
+            (min, max - min)
+        }
+        Scaler::Robust => {
+            let median = values.median().ok_or_else(missing)?;
+            let q1 = values
+                .quantile(0.25, QuantileMethod::Linear)?
+                .ok_or_else(missing)?;
+            let q3 = values
+                .quantile(0.75, QuantileMethod::Linear)?
+                .ok_or_else(missing)?;
+            (median, q3 - q1)
+        }
+    };
+
+    Ok(FittedStep::Affine {
+        column: column.to_string(),
+        scaler,
+        shift,
+        // a constant column would divide by zero, it just gets shifted instead
+        scale: if scale.is_finite() && scale > 0.0 {
+            scale
+        } else {
+            1.0
+        },
+    })
+}
+
+fn fit_one_hot(df: &DataFrame, column: &str) -> Result<FittedStep> {
+    let values = df.column(column)?;
+    let values = values.str().map_err(|_| {
+        Error::column(
+            column,
+            format!("has type {}, one-hot encoding needs strings", values.dtype()),
+        )
+    })?;
+
+    let mut categories: Vec<String> = values.into_iter().flatten().map(str::to_string).collect();
+    categories.sort();
+    categories.dedup();
+    Ok(FittedStep::OneHot {
+        column: column.to_string(),
+        categories,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(df: &DataFrame, column: &str) -> Vec<f64> {
+        data::f64_column(df, column).unwrap().into_no_null_iter().collect()
+    }
+
+    #[test]
+    fn fitted_parameters_carry_over_to_new_data() {
+        let df = df![
+            "a" => [1.0, 2.0, 3.0],
+            "b" => [0.0, 5.0, 10.0],
+            "c" => [1.0, 2.0, 3.0]
+        ]
+        .unwrap();
+        let pipeline = Pipeline::default()
+            .scale(Scaler::Standard, &["a"])
+            .scale(Scaler::MinMax, &["b"])
+            .scale(Scaler::Robust, &["c"]);
+        let (fitted, scaled) = pipeline.fit_transform(&df).unwrap();
+        assert_eq!(values(&scaled, "a"), [-1.0, 0.0, 1.0]); //mean 2, standard deviation 1
+        assert_eq!(values(&scaled, "b"), [0.0, 0.5, 1.0]);
+        assert_eq!(values(&scaled, "c"), [-1.0, 0.0, 1.0]); //median 2, quartiles 1.5 and 2.5
+
+        // new rows go through the parameters learned above, not their own
+        let new = df!["a" => [4.0], "b" => [20.0], "c" => [0.0]].unwrap();
+        let new = fitted.transform(&new).unwrap();
+        assert_eq!(values(&new, "a"), [2.0]);
+        assert_eq!(values(&new, "b"), [2.0]);
+        assert_eq!(values(&new, "c"), [-2.0]);
+    }
+
+    #[test]
+    fn constant_column_is_only_shifted() {
+        let df = df!["a" => [5.0, 5.0, 5.0]].unwrap();
+        for scaler in [Scaler::Standard, Scaler::MinMax, Scaler::Robust] {
+            let fitted = Pipeline::default().scale(scaler, &["a"]).fit(&df).unwrap();
+            let FittedStep::Affine { shift, scale, .. } = fitted.steps[0] else {
+                panic!("{scaler:?} should be an affine step");
+            };
+            assert_eq!((shift, scale), (5.0, 1.0), "{scaler:?}");
+            assert_eq!(values(&fitted.transform(&df).unwrap(), "a"), [0.0; 3]);
+        }
+    }
+
+    #[test]
+    fn one_hot_categories() {
+        let df = df!["plan" => ["pro", "basic", "pro"]].unwrap();
+        let fitted = Pipeline::default().one_hot("plan").fit(&df).unwrap();
+        let encoded = fitted.transform(&df).unwrap();
+        assert_eq!(encoded.get_column_names(), ["plan=basic", "plan=pro"]);
+        assert_eq!(values(&encoded, "plan=pro"), [1.0, 0.0, 1.0]);
+
+        // a plan that wasn't there while fitting matches none of the columns
+        let unseen = fitted.transform(&df!["plan" => ["plus"]].unwrap()).unwrap();
+        assert_eq!(values(&unseen, "plan=basic"), [0.0]);
+        assert_eq!(values(&unseen, "plan=pro"), [0.0]);
+
+        let numbers = df!["plan" => [1.0, 2.0]].unwrap();
+        let error = Pipeline::default().one_hot("plan").fit(&numbers).unwrap_err();
+        assert_eq!(error.code(), "column");
+    }
+
+    #[test]
+    fn log_needs_values_above_minus_one() {
+        let df = df!["a" => [0.0, 1.0]].unwrap();
+        let fitted = Pipeline::default().scale(Scaler::Log, &["a"]).fit(&df).unwrap();
+        assert_eq!(values(&fitted.transform(&df).unwrap(), "a"), [0.0, 2f64.ln()]);
+
+        for bad in [-1.0, -3.0] {
+            let error = fitted.transform(&df!["a" => [1.0, bad]].unwrap()).unwrap_err();
+            assert_eq!(error.code(), "column");
+            assert!(error.to_string().contains(&format!("got {bad}")), "{error}");
+        }
+        let negative = df!["a" => [-1.0]].unwrap();
+        assert!(Pipeline::default().scale(Scaler::Log, &["a"]).fit(&negative).is_err()); //fitting runs the step too
+    }
+}
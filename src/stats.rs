@@ -0,0 +1,355 @@
+use ndarray::{Array1, Array2, Axis};
+
+// Small numeric helpers the fitting module needs, written out by hand so we don't pull in a stats crate.
+
+// ln Γ(x) for x > 0, Lanczos approximation (g = 7, n = 9), good to ~15 digits
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // reflection formula keeps the approximation in its accurate range
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+// regularized incomplete beta function I_x(a, b), continued fraction from Numerical Recipes
+pub fn incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    // the continued fraction converges quickly only on this side, use the symmetry otherwise
+    if x > (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - incomplete_beta(1.0 - x, b, a);
+    }
+
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut fraction = d;
+
+    for m in 1..=300 {
+        let m = m as f64;
+
+        // even step
+        let numerator = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 + numerator * d;
+        d = if d.abs() < TINY { 1.0 / TINY } else { 1.0 / d };
+        c = 1.0 + numerator / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        fraction *= d * c;
+
+        // odd step
+        let numerator = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 + numerator * d;
+        d = if d.abs() < TINY { 1.0 / TINY } else { 1.0 / d };
+        c = 1.0 + numerator / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        let delta = d * c;
+        fraction *= delta;
+
+        if (delta - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+
+    front * fraction / a
+}
+
+// P(T <= t) for Student's t distribution with `dof` degrees of freedom
+pub fn student_t_cdf(t: f64, dof: f64) -> f64 {
+    let tail = 0.5 * incomplete_beta(dof / (dof + t * t), dof / 2.0, 0.5);
+    if t > 0.0 { 1.0 - tail } else { tail }
+}
+
+// t such that P(T <= t) = p, found by bisection on the cdf
+pub fn student_t_quantile(p: f64, dof: f64) -> f64 {
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    let (mut lo, mut hi) = (-1.0, 1.0);
+    while student_t_cdf(lo, dof) > p {
+        lo *= 2.0;
+    }
+    while student_t_cdf(hi, dof) < p {
+        hi *= 2.0;
+    }
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        if student_t_cdf(mid, dof) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+// inverse of a square matrix by Gauss-Jordan elimination with partial pivoting, None if it's singular
+pub fn invert(matrix: &Array2<f64>) -> Option<Array2<f64>> {
+    let n = matrix.nrows();
+    if n != matrix.ncols() {
+        return None;
+    }
+
+    let mut a = matrix.clone();
+    let mut inverse = Array2::<f64>::eye(n);
+    let scale = matrix.iter().fold(0.0f64, |m, v| m.max(v.abs())).max(1.0);
+
+    for col in 0..n {
+        // biggest pivot in this column keeps the rounding errors down
+        let pivot = (col..n).max_by(|&i, &j| a[(i, col)].abs().total_cmp(&a[(j, col)].abs()))?;
+        if a[(pivot, col)].abs() < 1e-12 * scale {
+            return None;
+        }
+        for k in 0..n {
+            a.swap((col, k), (pivot, k));
+            inverse.swap((col, k), (pivot, k));
+        }
+
+        let p = a[(col, col)];
+        for k in 0..n {
+            a[(col, k)] /= p;
+            inverse[(col, k)] /= p;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = a[(row, col)];
+            if factor == 0.0 {
+                continue;
+            }
+            for k in 0..n {
+                a[(row, k)] -= factor * a[(col, k)];
+                inverse[(row, k)] -= factor * inverse[(col, k)];
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+// z such that P(Z <= z) = p for the standard normal, Acklam's rational approximation (~1e-9 relative error)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const LOW: f64 = 0.024_25;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+// Eigenvalues and eigenvectors (as columns) of a symmetric matrix by cyclic Jacobi rotations,
+// sorted from the largest eigenvalue down.
+pub fn symmetric_eigen(matrix: &Array2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = matrix.nrows();
+    let mut a = matrix.clone();
+    let mut vectors = Array2::<f64>::eye(n);
+    let scale = matrix.iter().map(|v| v * v).sum::<f64>().max(f64::MIN_POSITIVE);
+
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|p| (0..n).filter(move |&q| q != p).map(move |q| (p, q)))
+            .map(|(p, q)| a[(p, q)] * a[(p, q)])
+            .sum();
+        if off_diagonal < 1e-24 * scale {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[(p, q)] == 0.0 {
+                    continue;
+                }
+                // rotation angle that zeroes a[p][q]
+                let theta = (a[(q, q)] - a[(p, p)]) / (2.0 * a[(p, q)]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for k in 0..n {
+                    let (kp, kq) = (a[(k, p)], a[(k, q)]);
+                    a[(k, p)] = c * kp - s * kq;
+                    a[(k, q)] = s * kp + c * kq;
+                }
+                for k in 0..n {
+                    let (pk, qk) = (a[(p, k)], a[(q, k)]);
+                    a[(p, k)] = c * pk - s * qk;
+                    a[(q, k)] = s * pk + c * qk;
+                }
+                for k in 0..n {
+                    let (kp, kq) = (vectors[(k, p)], vectors[(k, q)]);
+                    vectors[(k, p)] = c * kp - s * kq;
+                    vectors[(k, q)] = s * kp + c * kq;
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[(j, j)].total_cmp(&a[(i, i)]));
+    let values = order.iter().map(|&i| a[(i, i)]).collect();
+    let vectors = vectors.select(Axis(1), &order);
+    (values, vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn known_values() {
+        // from the usual tables
+        assert!(close(student_t_quantile(0.975, 10.0), 2.2281, 1e-4));
+        assert!(close(student_t_quantile(0.975, 1.0), 12.706, 1e-3));
+        assert!(close(student_t_quantile(0.025, 10.0), -2.2281, 1e-4)); //symmetric around 0
+        assert!(close(student_t_quantile(0.5, 4.0), 0.0, 1e-6));
+        assert!(close(student_t_cdf(2.2281, 10.0), 0.975, 1e-4));
+        assert!(close(normal_quantile(0.975), 1.95996, 1e-5));
+        assert!(close(normal_quantile(0.5), 0.0, 1e-9));
+        assert!(close(normal_quantile(0.001), -3.09023, 1e-5));
+
+        assert!(close(ln_gamma(5.0), 24f64.ln(), 1e-10)); //Γ(5) = 4!
+        assert!(close(ln_gamma(0.5), std::f64::consts::PI.sqrt().ln(), 1e-10));
+        assert!(close(ln_gamma(1.0), 0.0, 1e-10));
+
+        assert!(close(incomplete_beta(0.3, 1.0, 1.0), 0.3, 1e-10)); //uniform
+        assert!(close(incomplete_beta(0.5, 3.0, 3.0), 0.5, 1e-10)); //symmetric
+        assert!(close(incomplete_beta(0.2, 2.0, 3.0), 0.1808, 1e-10)); //6x²-8x³+3x⁴
+        assert_eq!(incomplete_beta(0.0, 2.0, 3.0), 0.0);
+        assert_eq!(incomplete_beta(1.0, 2.0, 3.0), 1.0);
+    }
+
+    #[test]
+    fn inverse() {
+        let a = array![[4.0, 7.0, 2.0], [3.0, 6.0, 1.0], [2.0, 5.0, 3.0]];
+        let inverse = invert(&a).unwrap();
+        let identity = inverse.dot(&a);
+        for ((i, j), value) in identity.indexed_iter() {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!(close(*value, expected, 1e-12), "({i}, {j}) = {value}");
+        }
+
+        let singular = array![[1.0, 2.0], [2.0, 4.0]]; //second row is twice the first
+        assert!(invert(&singular).is_none());
+    }
+
+    #[test]
+    fn eigen_decomposition() {
+        let a = array![[2.0, 1.0], [1.0, 2.0]];
+        let (values, vectors) = symmetric_eigen(&a);
+        assert!(close(values[0], 3.0, 1e-12) && close(values[1], 1.0, 1e-12));
+        // A v = λ v for every column, and the columns have length 1
+        for (i, vector) in vectors.columns().into_iter().enumerate() {
+            let moved = a.dot(&vector);
+            for (m, v) in moved.iter().zip(&vector) {
+                assert!(close(*m, values[i] * v, 1e-12));
+            }
+            assert!(close(vector.dot(&vector), 1.0, 1e-12));
+        }
+        assert!(close(vectors[(0, 0)].abs(), 0.5f64.sqrt(), 1e-12)); //along (1, 1)
+
+        // largest first, whatever order they're in on the diagonal
+        let diagonal = array![[1.0, 0.0, 0.0], [0.0, 5.0, 0.0], [0.0, 0.0, 3.0]];
+        let (values, vectors) = symmetric_eigen(&diagonal);
+        assert_eq!(values.to_vec(), [5.0, 3.0, 1.0]);
+        assert_eq!(vectors.column(0).to_vec(), [0.0, 1.0, 0.0]);
+
+        let b = array![[4.0, 1.0, 0.5], [1.0, 3.0, 0.2], [0.5, 0.2, 1.0]];
+        let (values, _) = symmetric_eigen(&b);
+        assert!(values.windows(2).into_iter().all(|pair| pair[0] >= pair[1]));
+        assert!(close(values.sum(), 8.0, 1e-10)); //the trace
+    }
+}
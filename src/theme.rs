@@ -0,0 +1,413 @@
+use plotters::style::RGBColor; //cargo add plotters -> for plotting
+
+// The colour palettes shared by the charts and the HTMX page (other-examples/static/index.html).
+// Named colours follow Catppuccin (https://catppuccin.com/palette), the charts pick roles out of them.
+
+// every named colour of a palette, same names as the CSS variables
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub rosewater: RGBColor,
+    pub flamingo: RGBColor,
+    pub pink: RGBColor,
+    pub mauve: RGBColor,
+    pub red: RGBColor,
+    pub maroon: RGBColor,
+    pub peach: RGBColor,
+    pub yellow: RGBColor,
+    pub green: RGBColor,
+    pub teal: RGBColor,
+    pub sky: RGBColor,
+    pub sapphire: RGBColor,
+    pub blue: RGBColor,
+    pub lavender: RGBColor,
+    pub text: RGBColor,
+    pub subtext1: RGBColor,
+    pub subtext0: RGBColor,
+    pub overlay2: RGBColor,
+    pub overlay1: RGBColor,
+    pub overlay0: RGBColor,
+    pub surface2: RGBColor,
+    pub surface1: RGBColor,
+    pub surface0: RGBColor,
+    pub base: RGBColor,
+    pub mantle: RGBColor,
+    pub crust: RGBColor,
+}
+
+impl Palette {
+    // (name, colour) pairs in the usual Catppuccin order
+    pub fn named(&self) -> [(&'static str, RGBColor); 26] {
+        [
+            ("rosewater", self.rosewater),
+            ("flamingo", self.flamingo),
+            ("pink", self.pink),
+            ("mauve", self.mauve),
+            ("red", self.red),
+            ("maroon", self.maroon),
+            ("peach", self.peach),
+            ("yellow", self.yellow),
+            ("green", self.green),
+            ("teal", self.teal),
+            ("sky", self.sky),
+            ("sapphire", self.sapphire),
+            ("blue", self.blue),
+            ("lavender", self.lavender),
+            ("text", self.text),
+            ("subtext1", self.subtext1),
+            ("subtext0", self.subtext0),
+            ("overlay2", self.overlay2),
+            ("overlay1", self.overlay1),
+            ("overlay0", self.overlay0),
+            ("surface2", self.surface2),
+            ("surface1", self.surface1),
+            ("surface0", self.surface0),
+            ("base", self.base),
+            ("mantle", self.mantle),
+            ("crust", self.crust),
+        ]
+    }
+}
+
+// a palette plus the colours charts cycle through for categories (e.g. clusters)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub palette: Palette,
+    pub accents: &'static [RGBColor],
+}
+
+// 0xRRGGBB -> RGBColor
+const fn hex(rgb: u32) -> RGBColor {
+    RGBColor((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+}
+
+const MOCHA: Palette = Palette {
+    rosewater: hex(0xf5e0dc),
+    flamingo: hex(0xf2cdcd),
+    pink: hex(0xf5c2e7),
+    mauve: hex(0xcba6f7),
+    red: hex(0xf38ba8),
+    maroon: hex(0xeba0ac),
+    peach: hex(0xfab387),
+    yellow: hex(0xf9e2af),
+    green: hex(0xa6e3a1),
+    teal: hex(0x94e2d5),
+    sky: hex(0x89dceb),
+    sapphire: hex(0x74c7ec),
+    blue: hex(0x89b4fa),
+    lavender: hex(0xb4befe),
+    text: hex(0xcdd6f4),
+    subtext1: hex(0xbac2de),
+    subtext0: hex(0xa6adc8),
+    overlay2: hex(0x9399b2),
+    overlay1: hex(0x7f849c),
+    overlay0: hex(0x6c7086),
+    surface2: hex(0x585b70),
+    surface1: hex(0x45475a),
+    surface0: hex(0x313244),
+    base: hex(0x1e1e2e),
+    mantle: hex(0x181825),
+    crust: hex(0x11111b),
+};
+
+const MACCHIATO: Palette = Palette {
+    rosewater: hex(0xf4dbd6),
+    flamingo: hex(0xf0c6c6),
+    pink: hex(0xf5bde6),
+    mauve: hex(0xc6a0f6),
+    red: hex(0xed8796),
+    maroon: hex(0xee99a0),
+    peach: hex(0xf5a97f),
+    yellow: hex(0xeed49f),
+    green: hex(0xa6da95),
+    teal: hex(0x8bd5ca),
+    sky: hex(0x91d7e3),
+    sapphire: hex(0x7dc4e4),
+    blue: hex(0x8aadf4),
+    lavender: hex(0xb7bdf8),
+    text: hex(0xcad3f5),
+    subtext1: hex(0xb8c0e0),
+    subtext0: hex(0xa5adcb),
+    overlay2: hex(0x939ab7),
+    overlay1: hex(0x8087a2),
+    overlay0: hex(0x6e738d),
+    surface2: hex(0x5b6078),
+    surface1: hex(0x494d64),
+    surface0: hex(0x363a4f),
+    base: hex(0x24273a),
+    mantle: hex(0x1e2030),
+    crust: hex(0x181926),
+};
+
+const FRAPPE: Palette = Palette {
+    rosewater: hex(0xf2d5cf),
+    flamingo: hex(0xeebebe),
+    pink: hex(0xf4b8e4),
+    mauve: hex(0xca9ee6),
+    red: hex(0xe78284),
+    maroon: hex(0xea999c),
+    peach: hex(0xef9f76),
+    yellow: hex(0xe5c890),
+    green: hex(0xa6d189),
+    teal: hex(0x81c8be),
+    sky: hex(0x99d1db),
+    sapphire: hex(0x85c1dc),
+    blue: hex(0x8caaee),
+    lavender: hex(0xbabbf1),
+    text: hex(0xc6d0f5),
+    subtext1: hex(0xb5bfe2),
+    subtext0: hex(0xa5adce),
+    overlay2: hex(0x949cbb),
+    overlay1: hex(0x838ba7),
+    overlay0: hex(0x737994),
+    surface2: hex(0x626880),
+    surface1: hex(0x51576d),
+    surface0: hex(0x414559),
+    base: hex(0x303446),
+    mantle: hex(0x292c3c),
+    crust: hex(0x232634),
+};
+
+const LATTE: Palette = Palette {
+    rosewater: hex(0xdc8a78),
+    flamingo: hex(0xdd7878),
+    pink: hex(0xea76cb),
+    mauve: hex(0x8839ef),
+    red: hex(0xd20f39),
+    maroon: hex(0xe64553),
+    peach: hex(0xfe640b),
+    yellow: hex(0xdf8e1d),
+    green: hex(0x40a02b),
+    teal: hex(0x179299),
+    sky: hex(0x04a5e5),
+    sapphire: hex(0x209fb5),
+    blue: hex(0x1e66f5),
+    lavender: hex(0x7287fd),
+    text: hex(0x4c4f69),
+    subtext1: hex(0x5c5f77),
+    subtext0: hex(0x6c6f85),
+    overlay2: hex(0x7c7f93),
+    overlay1: hex(0x8c8fa1),
+    overlay0: hex(0x9ca0b0),
+    surface2: hex(0xacb0be),
+    surface1: hex(0xbcc0cc),
+    surface0: hex(0xccd0da),
+    base: hex(0xeff1f5),
+    mantle: hex(0xe6e9ef),
+    crust: hex(0xdce0e8),
+};
+
+// Mocha's neutrals with the Okabe-Ito colours (https://jfly.uni-koeln.de/color/) as accents,
+// those stay distinguishable with the common kinds of colour blindness
+const COLORBLIND: Palette = Palette {
+    rosewater: hex(0xcc79a7), // reddish purple
+    flamingo: hex(0xcc79a7),
+    pink: hex(0xcc79a7),
+    mauve: hex(0xcc79a7),
+    red: hex(0xd55e00), // vermillion
+    maroon: hex(0xd55e00),
+    peach: hex(0xe69f00), // orange
+    yellow: hex(0xf0e442),
+    green: hex(0x009e73), // bluish green
+    teal: hex(0x009e73),
+    sky: hex(0x56b4e9), // sky blue
+    sapphire: hex(0x56b4e9),
+    blue: hex(0x0072b2),
+    lavender: hex(0x56b4e9),
+    ..MOCHA
+};
+
+impl Theme {
+    pub const MOCHA: Theme = Theme {
+        name: "mocha",
+        palette: MOCHA,
+        accents: &[
+            MOCHA.peach,
+            MOCHA.mauve,
+            MOCHA.sky,
+            MOCHA.green,
+            MOCHA.red,
+            MOCHA.yellow,
+            MOCHA.blue,
+            MOCHA.pink,
+        ],
+    };
+
+    pub const MACCHIATO: Theme = Theme {
+        name: "macchiato",
+        palette: MACCHIATO,
+        accents: &[
+            MACCHIATO.peach,
+            MACCHIATO.mauve,
+            MACCHIATO.sky,
+            MACCHIATO.green,
+            MACCHIATO.red,
+            MACCHIATO.yellow,
+            MACCHIATO.blue,
+            MACCHIATO.pink,
+        ],
+    };
+
+    pub const FRAPPE: Theme = Theme {
+        name: "frappe",
+        palette: FRAPPE,
+        accents: &[
+            FRAPPE.peach,
+            FRAPPE.mauve,
+            FRAPPE.sky,
+            FRAPPE.green,
+            FRAPPE.red,
+            FRAPPE.yellow,
+            FRAPPE.blue,
+            FRAPPE.pink,
+        ],
+    };
+
+    pub const LATTE: Theme = Theme {
+        name: "latte",
+        palette: LATTE,
+        accents: &[
+            LATTE.peach,
+            LATTE.mauve,
+            LATTE.sky,
+            LATTE.green,
+            LATTE.red,
+            LATTE.yellow,
+            LATTE.blue,
+            LATTE.pink,
+        ],
+    };
+
+    pub const COLORBLIND: Theme = Theme {
+        name: "colorblind",
+        palette: COLORBLIND,
+        accents: &[
+            COLORBLIND.peach,
+            COLORBLIND.sky,
+            COLORBLIND.green,
+            COLORBLIND.yellow,
+            COLORBLIND.blue,
+            COLORBLIND.red,
+            COLORBLIND.pink,
+        ],
+    };
+
+    pub const ALL: [Theme; 5] = [
+        Theme::MOCHA,
+        Theme::MACCHIATO,
+        Theme::FRAPPE,
+        Theme::LATTE,
+        Theme::COLORBLIND,
+    ];
+
+    // looks a theme up by name, "frappé" works as well as "frappe"
+    pub fn by_name(name: &str) -> Option<Theme> {
+        let name = name.trim().to_lowercase().replace('é', "e");
+        Theme::ALL.into_iter().find(|theme| theme.name == name)
+    }
+
+    // colour for the n-th category, wraps around when there are more categories than accents
+    pub fn accent(&self, n: usize) -> RGBColor {
+        self.accents[n % self.accents.len()]
+    }
+
+    // the palette as a CSS :root block of variables, e.g. --base: #1e1e2e;
+    pub fn css_variables(&self) -> String {
+        let mut css = String::from(":root {\n");
+        for (name, RGBColor(r, g, b)) in self.palette.named() {
+            css += &format!("    --{name}: #{r:02x}{g:02x}{b:02x};\n");
+        }
+        css += "}\n";
+        css
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::MOCHA
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn css_matches_the_page() {
+        // the variables index.html used to define by hand
+        let expected = [
+            ":root {",
+            "    --rosewater: #f5e0dc;",
+            "    --flamingo: #f2cdcd;",
+            "    --pink: #f5c2e7;",
+            "    --mauve: #cba6f7;",
+            "    --red: #f38ba8;",
+            "    --maroon: #eba0ac;",
+            "    --peach: #fab387;",
+            "    --yellow: #f9e2af;",
+            "    --green: #a6e3a1;",
+            "    --teal: #94e2d5;",
+            "    --sky: #89dceb;",
+            "    --sapphire: #74c7ec;",
+            "    --blue: #89b4fa;",
+            "    --lavender: #b4befe;",
+            "    --text: #cdd6f4;",
+            "    --subtext1: #bac2de;",
+            "    --subtext0: #a6adc8;",
+            "    --overlay2: #9399b2;",
+            "    --overlay1: #7f849c;",
+            "    --overlay0: #6c7086;",
+            "    --surface2: #585b70;",
+            "    --surface1: #45475a;",
+            "    --surface0: #313244;",
+            "    --base: #1e1e2e;",
+            "    --mantle: #181825;",
+            "    --crust: #11111b;",
+            "}",
+        ];
+        let css = Theme::MOCHA.css_variables();
+        assert_eq!(css.lines().collect::<Vec<_>>(), expected);
+
+        // every variable the page uses is in the exported block
+        let page = include_str!("../other-examples/static/index.html");
+        for used in page.split("var(--").skip(1) {
+            let name = &used[..used.find(')').unwrap()];
+            assert!(css.contains(&format!("--{name}: #")), "--{name} is not exported");
+        }
+    }
+
+    #[test]
+    fn lookup_by_name() {
+        assert_eq!(Theme::by_name("Frappé"), Some(Theme::FRAPPE));
+        assert_eq!(Theme::by_name(" LATTE "), Some(Theme::LATTE));
+        assert_eq!(Theme::by_name("solarized"), None);
+        for theme in Theme::ALL {
+            assert_eq!(Theme::by_name(theme.name), Some(theme));
+        }
+        assert_eq!(Theme::default(), Theme::MOCHA);
+    }
+
+    #[test]
+    fn accents_wrap_around() {
+        let theme = Theme::MOCHA;
+        let n = theme.accents.len();
+        assert_eq!(theme.accent(0), MOCHA.peach);
+        assert_eq!(theme.accent(n), theme.accent(0));
+        assert_eq!(theme.accent(2 * n + 3), theme.accent(3));
+    }
+
+    #[test]
+    fn colorblind_palette() {
+        // the seven Okabe-Ito colours, each one once
+        let okabe_ito = [0xe69f00, 0x56b4e9, 0x009e73, 0xf0e442, 0x0072b2, 0xd55e00, 0xcc79a7];
+        let accents: Vec<u32> = Theme::COLORBLIND
+            .accents
+            .iter()
+            .map(|&RGBColor(r, g, b)| u32::from_be_bytes([0, r, g, b]))
+            .collect();
+        assert_eq!(accents, okabe_ito);
+        // text and background stay the dark ones from mocha
+        assert_eq!(COLORBLIND.base, MOCHA.base);
+        assert_eq!(COLORBLIND.text, MOCHA.text);
+    }
+}
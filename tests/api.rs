@@ -0,0 +1,68 @@
+// The crate used from the outside, the way a dependent crate sees it.
+
+use polars::prelude::*;
+use rust_syntax_revision::clustering::{Algorithm, ClusterCount, Linkage};
+use rust_syntax_revision::fitting::{self, Model};
+use rust_syntax_revision::{
+    Error, List, Numberinator, PersistentList, digits, lessons, longest, parallel_min,
+};
+
+#[test]
+fn walkthrough_examples() {
+    assert_eq!(parallel_min(&(1..=100).rev().collect::<Vec<_>>()), Some(1));
+    assert_eq!(parallel_min(&[]), None);
+    assert_eq!(longest("long string is long", "xyz"), "long string is long");
+    assert_eq!(digits::reverse_digits(-120i64, 10), Ok(-21));
+
+    let mut numberinator = Numberinator::new(41, String::from("answer"));
+    numberinator.increment();
+    assert_eq!(numberinator.number, 42);
+
+    let mut list: List<i32> = [5, 10].into_iter().collect();
+    list.push_front(1);
+    list.reverse();
+    assert_eq!(format!("{list:?}"), "[10, 5, 1]");
+
+    let shared = PersistentList::new().prepend(10).prepend(5);
+    let longer = shared.prepend(1);
+    assert!(longer.tail().ptr_eq(&shared));
+}
+
+#[test]
+fn fit_and_cluster() {
+    let df = df![
+        "x" => [1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        "y" => [2.1, 3.9, 6.2, 7.8, 10.1, 12.0]
+    ]
+    .unwrap();
+    let result = fitting::fit_model(&df, &["x"], "y", Model::Linear).unwrap();
+    assert!((result.coefficients[0] - 2.0).abs() < 0.1);
+    assert!(result.r_squared > 0.99);
+
+    let missing = fitting::fit(&df, &["nope"], "y").unwrap_err();
+    assert!(matches!(missing, Error::Column { .. }));
+    assert_eq!(missing.code(), "column");
+
+    let records = ndarray::array![[0.0, 0.0], [0.1, 0.0], [5.0, 5.0], [5.1, 5.0]];
+    let clustering = rust_syntax_revision::clustering::cluster(
+        &records,
+        &Algorithm::Agglomerative(ClusterCount::Fixed(2), Linkage::Average),
+    )
+    .unwrap();
+    assert_eq!(clustering.labels[0], clustering.labels[1]);
+    assert_ne!(clustering.labels[0], clustering.labels[2]);
+}
+
+#[test]
+fn lessons_capture_their_output() {
+    let structs = lessons::find("structs").unwrap();
+    let output = structs.capture().unwrap();
+    assert!(output.contains("STRUCTS"));
+    assert!(output.contains("Number: 43, Name: Initially 42."));
+    assert!(lessons::find("no such lesson").is_none());
+
+    // errors the lesson handles are part of its output too, not sent to stderr
+    let functions = lessons::find("functions").unwrap().capture().unwrap();
+    assert!(functions.contains("Error: "));
+    assert!(functions.contains("File content3: Default content"));
+}